@@ -0,0 +1,254 @@
+//! the autocorrelation test: serial correlation at many lags.
+
+use super::*;
+
+/// max number of lags in one [AutocorrelationCalculation] (`64`).
+pub const MAX_LAGS: usize = 64;
+
+/// max value of a lag (`1024`).
+pub const MAX_LAG: usize = 1024;
+
+// ring buffer index is masked by this, so MAX_LAG must be a power of two.
+const HISTORY_MASK: u64 = (MAX_LAG as u64) - 1;
+
+/// Computes the autocorrelation coefficient for a set of lags, in a single pass.
+///
+/// the coefficient of lag `k` uses the same definition as [SerialCorrelationCoefficientCalculation]:
+/// every sample `u[i]` is paired with `u[(i + k) % n]`, so lag 1 is exactly the serial correlation coefficient.
+#[derive(Debug, Clone, Copy)]
+pub struct AutocorrelationCalculation {
+    /// lags to compute
+    lags: [u32; MAX_LAGS],
+    /// number of lags
+    lags_len: usize,
+    /// sum of `u[i] * u[i + k]` for each lag, without wrapped pairs
+    sums: [u64; MAX_LAGS],
+    /// the last samples (ring buffer)
+    history: [u8; MAX_LAG],
+    /// the first samples
+    head: [u8; MAX_LAG],
+    /// sum of samples
+    t2: u64,
+    /// sum of squared samples
+    t3: u64,
+    /// total samples processed
+    total: u64,
+    /// sampling mode
+    mode: Mode,
+}
+
+impl AutocorrelationCalculation {
+    /// creates new blanket state for autocorrelation of `lags`.
+    ///
+    /// # Panics
+    /// if there is more than [MAX_LAGS] lags, or any lag is not in `1..=MAX_LAG`.
+    #[inline(always)]
+    pub const fn new(lags: &[u32]) -> Self {
+        Self::with_mode(Mode::Bytes, lags)
+    }
+
+    /// creates new blanket state for autocorrelation of `lags` with the provided [Mode].
+    ///
+    /// # Panics
+    /// if there is more than [MAX_LAGS] lags, or any lag is not in `1..=MAX_LAG`.
+    pub const fn with_mode(mode: Mode, lags: &[u32]) -> Self {
+        assert!(Self::is_valid_lags(lags), "invalid lags for autocorrelation");
+
+        let mut this =
+            Self {
+                lags: [0; MAX_LAGS],
+                lags_len: lags.len(),
+                sums: [0; MAX_LAGS],
+                history: [0; MAX_LAG],
+                head: [0; MAX_LAG],
+                t2: 0,
+                t3: 0,
+                total: 0,
+                mode,
+            };
+        unwrap!(copy_from_slice(&mut this.lags, lags));
+        this
+    }
+
+    /// creates new blanket state for autocorrelation of every lag in `first..=last`.
+    ///
+    /// # Panics
+    /// same as [AutocorrelationCalculation::new].
+    pub const fn lag_range(mode: Mode, first: u32, last: u32) -> Self {
+        let mut lags = [0u32; MAX_LAGS];
+        let mut len = 0;
+        let mut lag = first;
+        while lag <= last {
+            assert!(len < MAX_LAGS, "too many lags for autocorrelation");
+            lags[len] = lag;
+            len += 1;
+            lag += 1;
+        }
+        Self::with_mode(mode, lags.split_at(len).0)
+    }
+
+    /// creates new blanket state for autocorrelation of lags `1, 2, 4, ...` up to `max`.
+    ///
+    /// # Panics
+    /// same as [AutocorrelationCalculation::new].
+    pub const fn powers_of_two(mode: Mode, max: u32) -> Self {
+        let mut lags = [0u32; MAX_LAGS];
+        let mut len = 0;
+        let mut lag = 1;
+        while lag <= max {
+            lags[len] = lag;
+            len += 1;
+            lag *= 2;
+        }
+        Self::with_mode(mode, lags.split_at(len).0)
+    }
+
+    /// checks whether `lags` can be used by [AutocorrelationCalculation::new].
+    pub const fn is_valid_lags(lags: &[u32]) -> bool {
+        if lags.len() > MAX_LAGS {
+            return false;
+        }
+        let mut i = 0;
+        while i < lags.len() {
+            if lags[i] == 0 || lags[i] as usize > MAX_LAG {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// get the lags of current state.
+    #[inline(always)]
+    pub const fn lags(&self) -> &[u32] {
+        self.lags.split_at(self.lags_len).0
+    }
+
+    /// get the samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total
+    }
+
+    /// apply byte stream to autocorrelation state.
+    #[inline(always)]
+    pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        let bytes_len = bytes.len();
+
+        let mut i = 0;
+        let mut j;
+        let mut bits;
+        while i < bytes_len {
+            match self.mode {
+                Mode::Bytes => {
+                    self.push(bytes[i]);
+                },
+                Mode::Bits(order) => {
+                    bits = order.expand(bytes[i]);
+                    j = 0;
+                    while j < 8 {
+                        self.push(bits[j]);
+                        j += 1;
+                    }
+                },
+            }
+            i += 1;
+        }
+
+        self
+    }
+
+    /// apply one sample to autocorrelation state.
+    #[inline(always)]
+    const fn push(&mut self, b: u8) {
+        let un = b as u64;
+        let t = self.total;
+
+        let mut i = 0;
+        while i < self.lags_len {
+            let lag = self.lags[i] as u64;
+            if t >= lag {
+                // mul is loss-less for native integer type
+                self.sums[i] += (self.history[((t - lag) & HISTORY_MASK) as usize] as u64) * un;
+            }
+            i += 1;
+        }
+
+        if (t as usize) < MAX_LAG {
+            self.head[t as usize] = b;
+        }
+        self.history[(t & HISTORY_MASK) as usize] = b;
+        self.t2 += un;
+        self.t3 += un * un;
+        self.total += 1;
+    }
+
+    /// get the autocorrelation coefficient of `lags()[index]`.
+    ///
+    /// returns NaN if all samples are equal, or there is not more samples than the lag.
+    pub const fn coefficient(&self, index: usize) -> Dec {
+        assert!(index < self.lags_len, "index out of range of lags");
+
+        let lag = self.lags[index] as u64;
+        let n = self.total;
+        if n <= lag {
+            return Dec::NAN;
+        }
+
+        // close the wrapped pairs: u[n - lag + j] with u[j].
+        let mut t1 = self.sums[index];
+        let mut j = 0;
+        while j < lag {
+            let last = self.history[((n - lag + j) & HISTORY_MASK) as usize] as u64;
+            t1 += last * (self.head[j as usize] as u64);
+            j += 1;
+        }
+
+        let total = Dec::from_u64(n);
+        let t2 = Dec::from_u64(self.t2).powi(2);
+        let den = total.mul(Dec::from_u64(self.t3)).sub(t2);
+        if den.eq(&Dec::ZERO) {
+            return Dec::NAN;
+        }
+        total.mul(Dec::from_u64(t1)).sub(t2).div(den)
+    }
+
+    /// standard score of the coefficient of `lags()[index]`, see [sc::sc_z_score].
+    #[inline(always)]
+    pub const fn z_score(&self, index: usize) -> Dec {
+        sc::sc_z_score(self.coefficient(index), self.total)
+    }
+
+    /// get the outcome of `lags()[index]`.
+    ///
+    /// the p-value uses the same asymptotic distribution as the serial correlation coefficient, see [sc::sc_z_score].
+    pub const fn outcome(&self, index: usize) -> TestOutcome {
+        TestOutcome::new(self.coefficient(index), sc::sc_expected(self.total), self.total)
+            .with_p_value(chisqr::normal_p_two_sided(self.z_score(index)))
+    }
+
+    /// iterate over every lag and its outcome.
+    pub fn outcomes(&self) -> impl Iterator<Item = (u32, TestOutcome)> + '_ {
+        self.lags().iter().enumerate().map(|(index, &lag)| { (lag, self.outcome(index)) })
+    }
+}
+
+/// one line for each lag: coefficient, z-score and p-value.
+impl core::fmt::Display for AutocorrelationCalculation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "Autocorrelation of {} samples:", self.total)?;
+        for index in 0..self.lags_len {
+            let r = format::CFloat(self.coefficient(index), 6);
+            let z = format::CFloat(self.z_score(index), 2);
+            let p = format::CFloat(chisqr::normal_p_two_sided(self.z_score(index)), 4);
+            writeln!(f, "lag {:>4}: {r} ({z} standard deviations, p-value {p})", self.lags[index])?;
+        }
+        Ok(())
+    }
+}
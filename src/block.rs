@@ -0,0 +1,185 @@
+//! the block (n-gram) entropy test.
+
+use super::*;
+use alloc::vec::Vec;
+
+/// max bits of a block (`64`): 8 bytes, or 64 bits in bit mode.
+pub const MAX_BLOCK_BITS: u32 = 64;
+
+/// Computes the shannon entropy of overlapping blocks of `n` samples, for a range of `n`.
+///
+/// for samples `u[0..N]`, the blocks of length `n` are `u[i..i + n]` for every `i` in `0..=N - n`.
+/// the block entropy `H(n)` is the shannon entropy of those blocks, then `H(n) / n` and the increment `H(n) - H(n - 1)`
+/// are estimates of the entropy rate in bits per sample. for a repeating counter, `H(1)` is the max (8 bits per byte)
+/// while the increments of `n >= 2` are zero.
+///
+/// `H(n)` is computed from observed blocks only (plug-in estimate), so it can not exceed `log2(N - n + 1)`:
+/// it is meaningful only if `N` is much larger than the number of possible blocks.
+#[derive(Debug, Clone)]
+pub struct BlockEntropyCalculation {
+    /// the shortest block length to report
+    first: u32,
+    /// the longest block length
+    last: u32,
+    /// occurrence counts of blocks of every length in `first - 1 ..= last` (but at least 1)
+    counts: Vec<SymbolTable>,
+    /// the last samples, the newest sample is the least significant
+    window: u64,
+    /// total samples processed
+    total: u64,
+    /// sampling mode
+    mode: Mode,
+}
+
+impl BlockEntropyCalculation {
+    /// create new blanket state for block entropy of lengths `1..=last`.
+    ///
+    /// # Panics
+    /// same as [BlockEntropyCalculation::with_range].
+    #[inline(always)]
+    pub fn new(last: u32) -> Self {
+        Self::with_range(Mode::Bytes, 1, last)
+    }
+
+    /// create new blanket state for block entropy of lengths `first..=last` with the provided [Mode].
+    ///
+    /// # Panics
+    /// if it is not valid, see [BlockEntropyCalculation::is_valid].
+    pub fn with_range(mode: Mode, first: u32, last: u32) -> Self {
+        assert!(Self::is_valid(mode, first, last), "invalid block lengths");
+        let lowest = if first > 1 { first - 1 } else { 1 };
+        Self {
+            first,
+            last,
+            counts: (lowest..=last).map(|n| { SymbolTable::new(n * mode.sample_bits()) }).collect(),
+            window: 0,
+            total: 0,
+            mode,
+        }
+    }
+
+    /// checks whether `first..=last` is not empty, starts from 1, and blocks of `last` samples fit in [MAX_BLOCK_BITS].
+    #[inline(always)]
+    pub const fn is_valid(mode: Mode, first: u32, last: u32) -> bool {
+        first >= 1 && first <= last && last <= MAX_BLOCK_BITS / mode.sample_bits()
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// get the block lengths of current state.
+    #[inline(always)]
+    pub const fn lengths(&self) -> core::ops::RangeInclusive<u32> {
+        self.first..=self.last
+    }
+
+    /// get the number of samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total
+    }
+
+    /// the index of length `n` in `counts`.
+    #[inline(always)]
+    const fn index(&self, n: u32) -> usize {
+        (n - (self.last + 1 - self.counts.len() as u32)) as usize
+    }
+
+    /// apply byte stream to block entropy state.
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        for &byte in bytes {
+            match self.mode {
+                Mode::Bytes => {
+                    self.push(byte);
+                },
+                Mode::Bits(order) => {
+                    for bit in order.expand(byte) {
+                        self.push(bit);
+                    }
+                },
+            }
+        }
+        self
+    }
+
+    /// apply one sample to block entropy state.
+    #[inline(always)]
+    fn push(&mut self, sample: u8) {
+        let sample_bits = self.mode.sample_bits();
+        self.window = self.window.checked_shl(sample_bits).unwrap_or(0) | (sample as u64);
+        self.total += 1;
+
+        let lowest = self.last + 1 - self.counts.len() as u32;
+        for (n, counts) in (lowest..=self.last).zip(self.counts.iter_mut()) {
+            if self.total < n as u64 {
+                break;
+            }
+            let block_bits = n * sample_bits;
+            let mask = if block_bits >= 64 { u64::MAX } else { (1 << block_bits) - 1 };
+            counts.add(self.window & mask);
+        }
+    }
+
+    /// the number of blocks of length `n`, that is `N - n + 1`.
+    #[inline(always)]
+    pub const fn blocks(&self, n: u32) -> u64 {
+        self.total.saturating_sub(n as u64 - 1)
+    }
+
+    /// block entropy `H(n)` in bits per block.
+    ///
+    /// `H(0)` is zero. returns NaN if there is no block of length `n`.
+    ///
+    /// # Panics
+    /// if `n` is not zero and not in `first - 1 ..= last`.
+    pub fn block_entropy(&self, n: u32) -> Dec {
+        if n == 0 {
+            return Dec::ZERO;
+        }
+        self.counts[self.index(n)].shannon(self.blocks(n))
+    }
+
+    /// the per-sample estimate `H(n) / n`, in bits per sample.
+    pub fn per_sample(&self, n: u32) -> Dec {
+        self.block_entropy(n).div(Dec::from_u32(n))
+    }
+
+    /// the conditional entropy `H(n) - H(n - 1)`, that is the entropy rate estimate in bits per sample.
+    ///
+    /// `H(n)` has one block less than `H(n - 1)`, so this is clamped to zero if it is slightly negative.
+    pub fn rate(&self, n: u32) -> Dec {
+        let rate = self.block_entropy(n).sub(self.block_entropy(n - 1));
+        if rate.lt(&Dec::ZERO) { Dec::ZERO } else { rate }
+    }
+
+    /// the lowest entropy rate estimate of all block lengths, in bits per sample.
+    pub fn finalize(&self) -> Dec {
+        let mut lowest = Dec::NAN;
+        for n in self.lengths() {
+            let rate = self.rate(n);
+            if lowest.is_nan() || rate.lt(&lowest) {
+                lowest = rate;
+            }
+        }
+        lowest
+    }
+}
+
+/// one line for each block length: block entropy, per-sample estimate and entropy rate.
+impl core::fmt::Display for BlockEntropyCalculation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let unit = self.mode.unit();
+        writeln!(f, "Block entropy of {} samples:", self.total)?;
+        for n in self.lengths() {
+            writeln!(
+                f,
+                "n = {n:>2}: {} bits per block, {} bits per {unit}, rate {} bits per {unit}.",
+                format::CFloat(self.block_entropy(n), 6), format::CFloat(self.per_sample(n), 6), format::CFloat(self.rate(n), 6),
+            )?;
+        }
+        Ok(())
+    }
+}
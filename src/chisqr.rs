@@ -3,9 +3,11 @@
 use super::*;
 
 /// Computes the Chi Square probability of a random dataset this extreme.
+///
+/// it counts byte or bit samples in [Buckets] by default, or symbols of other widths in [SymbolCounts].
 #[derive(Debug, Copy, Clone)]
-pub struct ChiSquareCalculation {
-    pub(crate) buckets: [u64; 256],
+pub struct ChiSquareCalculation<C = Buckets> {
+    pub(crate) counts: C,
     pub(crate) total_buckets: u64,
 }
 
@@ -16,70 +18,116 @@ impl Default for ChiSquareCalculation {
     }
 }
 
-impl ChiSquareCalculation {
-    /// the blanket state (initial value) of [ChiSquareCalculation].
-    pub const INIT: Self =
+impl<C: Counts> ChiSquareCalculation<C> {
+    /// creates new blanket state for chi-square calculation of the provided empty counts.
+    #[inline(always)]
+    pub const fn with_counts(counts: C) -> Self {
         Self {
-            buckets: [0; 256],
+            counts,
             total_buckets: 0,
-        };
+        }
+    }
 
-    /// creates new blanket state for chi-square calculation.
-    ///
-    /// this just copy from [ChiSquareCalculation::INIT].
+    /// get the occurrence counts of current state.
     #[inline(always)]
-    pub const fn new() -> Self {
-        Self::INIT
+    pub const fn counts(&self) -> &C {
+        &self.counts
     }
 
-    /// apply byte stream to chi-square state.
+    /// get the samples of current state.
     #[inline(always)]
-    pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
-        let mut i = 0;
-        let bytes_len = bytes.len();
-        while i < bytes_len {
-            self.buckets[bytes[i] as usize] += 1;
-            i += 1;
-        }
-        self.total_buckets += bytes_len as u64;
+    pub const fn samples(&self) -> u64 {
+        self.total_buckets
+    }
 
+    /// apply byte stream to chi-square state.
+    #[inline(always)]
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.total_buckets += self.counts.tally(bytes);
         self
     }
 
     /// get finalize chi-square result of current byte stream.
     #[inline(always)]
-    pub const fn finalize(&self) -> Dec {
-        chi_statistic(&self.buckets, self.total_buckets)
+    pub fn finalize(&self) -> Dec {
+        self.counts.chi(self.total_buckets)
     }
 
-    /// returns `self.finalize()` and `probability_chi_sq(self.finalize())`
+    /// the degrees of freedom of chi-square statistic, that is one less than the number of bins.
     #[inline(always)]
-    pub const fn finalize_probability(&self) -> (Dec, Dec) {
+    pub fn degrees_of_freedom(&self) -> u64 {
+        self.counts.bins() - 1
+    }
+
+    /// returns `self.finalize()` and `chi_square_survival(self.finalize(), self.degrees_of_freedom())`
+    #[inline(always)]
+    pub fn finalize_probability(&self) -> (Dec, Dec) {
         let f = self.finalize();
+        (f, chi_square_survival(f, self.degrees_of_freedom()))
+    }
 
-        //(f, pochisq(f, 255))
-        //(f, pochisq(f, self.samples()))
-        (f, probability_chi_sq(f, 127))
+    /// get the outcome of current state, that is expected to be [ChiSquareCalculation::degrees_of_freedom] for random input.
+    ///
+    /// p-values close to 1 is also considered as non-random (the fit is too good).
+    #[inline(always)]
+    pub fn outcome(&self) -> TestOutcome {
+        let (chi, chi_prob) = self.finalize_probability();
+        TestOutcome::new(chi, Dec::from_u64(self.degrees_of_freedom()), self.total_buckets)
+            .with_p_value(chi_prob)
+            .with_both_tails(true)
     }
+}
 
-    /// get the samples of current state.
+impl ChiSquareCalculation {
+    /// the blanket state (initial value) of [ChiSquareCalculation].
+    pub const INIT: Self = Self::with_counts(Buckets::INIT);
+
+    /// creates new blanket state for chi-square calculation.
+    ///
+    /// this just copy from [ChiSquareCalculation::INIT].
     #[inline(always)]
-    pub const fn samples(&self) -> u64 {
-        self.total_buckets
+    pub const fn new() -> Self {
+        Self::INIT
+    }
+
+    /// creates new blanket state for chi-square calculation with the provided [Mode].
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        Self::with_counts(Buckets::with_mode(mode))
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.counts.mode()
+    }
+
+    /// get the occurrence counts of current state.
+    #[inline(always)]
+    pub const fn histogram(&self) -> Histogram<'_> {
+        self.counts.histogram(self.total_buckets)
     }
 
     /// oneshot test function for small data.
     ///
     /// this is equivalent to `Self::new().update(data).finalize_probability()`.
     #[inline(always)]
-    pub const fn test(data: &[u8]) -> (Dec, Dec) {
+    pub fn test(data: &[u8]) -> (Dec, Dec) {
         let mut this = Self::INIT;
         this.update(data);
         this.finalize_probability()
     }
 }
 
-impl EntropyTest for ChiSquareCalculation {
+impl ChiSquareCalculation<SymbolCounts> {
+    /// creates new blanket state for chi-square calculation of `bits` wide symbols, see [SymbolCounts::new].
+    #[inline(always)]
+    pub fn with_symbols(bits: u32, order: BitOrder) -> Self {
+        Self::with_counts(SymbolCounts::new(bits).with_order(order))
+    }
+}
+
+impl<C: Counts> EntropyTest for ChiSquareCalculation<C> {
     #[inline(always)]
     fn update(&mut self, bytes: &[u8]) {
         Self::update(self, bytes);
@@ -89,6 +137,10 @@ impl EntropyTest for ChiSquareCalculation {
     fn finalize(&self) -> Dec {
         Self::finalize(self)
     }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
 }
 
 /// max value of e^x (`20.0`)
@@ -97,11 +149,11 @@ pub const MAX_X: Dec = dec!(20.0);
 /// negative value of [MAX_X].
 pub const NEG_MAX_X: Dec = MAX_X.neg();
 
-const LOG_SQRT_PI_D256: &'static str = "0.57236494292470008707171367567652935582364740645765578575681153573606888494239";
-const I_SQRT_PI_D256: &'static str = "0.56418958354775628694807945156077258584405062932899885684408572171064246844150";
+const LOG_SQRT_PI_D256: &str = "0.57236494292470008707171367567652935582364740645765578575681153573606888494239";
+const I_SQRT_PI_D256: &str = "0.56418958354775628694807945156077258584405062932899885684408572171064246844150";
 
-const LOG_SQRT_PI_D64: &'static str = "0.5723649429247000870";
-const I_SQRT_PI_D64: &'static str = "0.5641895835477562870";
+const LOG_SQRT_PI_D64: &str = "0.5723649429247000870";
+const I_SQRT_PI_D64: &str = "0.5641895835477562870";
 
 /// python: `math.log(math.sqrt(math.pi))`
 ///
@@ -148,19 +200,25 @@ pub const HALF_MAX_Z: Dec = MAX_Z.div(TWO);
 /// Compute X^2 statistic
 #[inline(always)]
 pub const fn chi_statistic(buckets: &[u64; 256], total_buckets: u64) -> Dec {
+    chi_statistic_bins(buckets, 256, total_buckets)
+}
+
+/// Compute X^2 statistic over the first `bins` buckets.
+#[inline(always)]
+pub const fn chi_statistic_bins(buckets: &[u64], bins: usize, total_buckets: u64) -> Dec {
     if total_buckets == 0 {
         return Dec::NAN;
     }
 
     let total = Dec::from_u64(total_buckets);
     let mut chi_sq = ZERO;
-    let exp = total.div(dec!(256.0));
+    let exp = total.div(Dec::from_usize(bins));
 
     let mut i = 0;
     let mut b;
     let mut a;
 
-    while i < 256 {
+    while i < bins {
         b = Dec::from_u64(buckets[i]);
 
         a = b.sub(exp);
@@ -171,68 +229,269 @@ pub const fn chi_statistic(buckets: &[u64; 256], total_buckets: u64) -> Dec {
 
     chi_sq
 }
-/*
- * FUNCTION pochisq: probability of chi sqaure value
-
- * ALGORITHM Compute probability of chi square value.
-        Adapted from:
-                Hill, I. D. and Pike, M. C.  Algorithm 299
-                Collected Algorithms for the CACM 1967 p. 243
-
-        [FIXME]
-        Updated for rounding errors based on remark in
-                ACM TOMS June 1985, page 185
-            (??? is it causes bugs? because fastnum has no rounding errors)
-*/
-// FIXME returns incorrect result currently
+const HALF_LN_2PI_D256: &str = "0.91893853320467274178032973640561763986139747363778341281715154048276569592726";
+const HALF_LN_2PI_D64: &str = "0.9189385332046727418";
+
+/// python: `math.log(2 * math.pi) / 2`
+///
+/// (approx `0.9189385332046727417803297`)
+pub const HALF_LN_2PI: Dec =
+    if cfg!(feature="lite") {
+        Dec::parse_str(HALF_LN_2PI_D64, DEC_CTX)
+    } else {
+        Dec::parse_str(HALF_LN_2PI_D256, DEC_CTX)
+    };
+
+/// [ln_gamma] uses the stirling series for arguments not less than this, and the recurrence for smaller ones.
+const STIRLING_MIN: Dec = dec!(20);
+
+/// coefficients of the stirling series: `B(2k) / (2k * (2k - 1))` as `(numerator, denominator)`.
+const STIRLING_COEFFS: [(i32, i32); 10] = [
+    (1, 12), (-1, 360), (1, 1260), (-1, 1680), (1, 1188),
+    (-691, 360360), (1, 156), (-3617, 122400), (43867, 244188), (-174611, 125400),
+];
+
+/// the relative precision that incomplete gamma iterations stop at.
+const GAMMA_EPSILON: Dec = Dec::EPSILON;
+
+/// max iterations of incomplete gamma calculation.
+const GAMMA_MAX_ITER: u32 = 100_000;
+
+/// tiny value that avoids division by zero in the continued fraction.
+const GAMMA_FPMIN: Dec = dec!(1e-300);
+
+/// `exp(x)` is considered as zero if `x` is less than this (it is about `1e-8686`).
+const GAMMA_MIN_EXP: Dec = dec!(-20000);
+
+/// natural logarithm of the gamma function, for `a > 0`.
+///
+/// returns NaN if `a` is NaN or not positive.
+/// this uses the stirling series, that is accurate to about 25 significant digits.
 #[inline(always)]
-pub(crate) const fn _pochisq(chi_sq: Dec, df: u16) -> Dec {
-    if chi_sq.le(&ZERO) || df < 1 {
-        return ONE;
+pub const fn ln_gamma(a: Dec) -> Dec {
+    if a.is_nan() || a.le(&ZERO) {
+        return Dec::NAN;
     }
 
-    let mut x = chi_sq;
+    // lgamma(a) = lgamma(a + n) - ln(a * (a + 1) * ... * (a + n - 1))
+    let mut z = a;
+    let mut shift = ONE;
+    while z.lt(&STIRLING_MIN) {
+        shift = shift.mul(z);
+        z = z.add(ONE);
+    }
 
-    let mut e;
-    let mut c;
-    let mut z;
+    z.sub(HALF_ONE).mul(z.ln()).sub(z).add(HALF_LN_2PI).add(stirling_series(z)).sub(ln(shift))
+}
 
-    let a = HALF_ONE.mul(x);
-    let even = (df & 1) == 0;
-
-    let y = if df > 1 { ex(a.neg()) } else { ZERO };
-
-    let mut s = if even { y } else { TWO.mul(poz(x.sqrt().neg())) };
-    if df > 2 {
-        x = HALF_ONE.mul(Dec::from_u16(df - 1));
-        z = if even { ONE } else { HALF_ONE };
-        if a.gt(&MAX_X) {
-            e = if even { ZERO } else { LOG_SQRT_PI };
-            c = a.ln();
-            while z.le(&x) {
-                e = e.add(z.ln());
-                s = s.add(c.mul(z).sub(a).sub(e));
-                z = z.add(ONE);
-            }
-            s
+/// the sum of correction terms of the stirling series, for `z >= STIRLING_MIN`.
+#[inline(always)]
+const fn stirling_series(z: Dec) -> Dec {
+    let zinv = ONE.div(z);
+    let zinv2 = zinv.mul(zinv);
+    let mut term = zinv;
+    let mut series = ZERO;
+    let mut i = 0;
+    while i < STIRLING_COEFFS.len() {
+        let (num, den) = STIRLING_COEFFS[i];
+        series = series.add(Dec::from_i32(num).div(Dec::from_i32(den)).mul(term));
+        term = term.mul(zinv2);
+        i += 1;
+    }
+    series
+}
+
+/// `x^a * e^(-x) / gamma(a)`, the common factor of incomplete gamma.
+#[inline(always)]
+const fn gamma_prefix(a: Dec, x: Dec) -> Dec {
+    let e =
+        if a.lt(&STIRLING_MIN) {
+            a.mul(ln(x)).sub(x).sub(ln_gamma(a))
         } else {
-            e = if even { ONE } else { I_SQRT_PI.div(a.sqrt()) };
-            c = ZERO;
-            while z.le(&x) {
-                e = e.mul(a.div(z));
-                c = c.add(e);
-                z = z.add(ONE);
-            }
-            c.mul(y).add(s)
+            // expanded with the stirling series, that avoids cancellation of large terms.
+            a.mul(ln(x.div(a))).add(a.sub(x)).add(HALF_ONE.mul(a.ln())).sub(HALF_LN_2PI).sub(stirling_series(a))
+        };
+    if e.lt(&GAMMA_MIN_EXP) {
+        ZERO
+    } else {
+        e.exp()
+    }
+}
+
+/// regularized lower incomplete gamma by the series, for `x < a + 1`.
+#[inline(always)]
+const fn gamma_p_series(a: Dec, x: Dec) -> Dec {
+    let mut ap = a;
+    let mut del = ONE.div(a);
+    let mut sum = del;
+    let mut i = 0;
+    while i < GAMMA_MAX_ITER {
+        ap = ap.add(ONE);
+        del = del.mul(x).div(ap);
+        sum = sum.add(del);
+        if del.abs().lt(&sum.abs().mul(GAMMA_EPSILON)) {
+            break;
+        }
+        i += 1;
+    }
+    sum.mul(gamma_prefix(a, x))
+}
+
+/// regularized upper incomplete gamma by the continued fraction (modified Lentz), for `x >= a + 1`.
+#[inline(always)]
+const fn gamma_q_fraction(a: Dec, x: Dec) -> Dec {
+    let mut b = x.add(ONE).sub(a);
+    let mut c = ONE.div(GAMMA_FPMIN);
+    let mut d = ONE.div(b);
+    let mut h = d;
+    let mut i = 1;
+    while i <= GAMMA_MAX_ITER {
+        let n = Dec::from_u32(i);
+        let an = n.neg().mul(n.sub(a));
+        b = b.add(TWO);
+
+        d = an.mul(d).add(b);
+        if d.abs().lt(&GAMMA_FPMIN) {
+            d = GAMMA_FPMIN;
+        }
+        c = b.add(an.div(c));
+        if c.abs().lt(&GAMMA_FPMIN) {
+            c = GAMMA_FPMIN;
+        }
+        d = ONE.div(d);
+
+        let del = d.mul(c);
+        h = h.mul(del);
+        if del.sub(ONE).abs().lt(&GAMMA_EPSILON) {
+            break;
         }
+        i += 1;
+    }
+    h.mul(gamma_prefix(a, x))
+}
+
+/// regularized lower incomplete gamma function `P(a, x)`.
+///
+/// returns NaN if any argument is NaN, `a` is not positive or `x` is negative.
+#[inline(always)]
+pub const fn gamma_p(a: Dec, x: Dec) -> Dec {
+    if a.is_nan() || x.is_nan() || a.le(&ZERO) || x.lt(&ZERO) {
+        return Dec::NAN;
+    }
+    if x.eq(&ZERO) {
+        return ZERO;
+    }
+
+    if x.lt(&a.add(ONE)) {
+        gamma_p_series(a, x)
     } else {
-        s
+        ONE.sub(gamma_q_fraction(a, x))
     }
 }
 
+/// regularized upper incomplete gamma function `Q(a, x) = 1 - P(a, x)`.
+///
+/// returns NaN if any argument is NaN, `a` is not positive or `x` is negative.
+#[inline(always)]
+pub const fn gamma_q(a: Dec, x: Dec) -> Dec {
+    if a.is_nan() || x.is_nan() || a.le(&ZERO) || x.lt(&ZERO) {
+        return Dec::NAN;
+    }
+    if x.eq(&ZERO) {
+        return ONE;
+    }
+
+    if x.lt(&a.add(ONE)) {
+        ONE.sub(gamma_p_series(a, x))
+    } else {
+        gamma_q_fraction(a, x)
+    }
+}
+
+/// above this degrees of freedom, [chi_square_survival] uses the Wilson-Hilferty approximation (`2^24`).
+///
+/// the series of `P(a, x)` does not converge in the max iterations for such large `a`,
+/// while the error of the approximation is already less than `1e-9` here.
+pub const WILSON_HILFERTY_MIN_DF: u64 = 1 << 24;
+
+/// survival function of the chi-square distribution with `df` degrees of freedom.
+///
+/// that is the probability of a random dataset has chi-square statistic greater than `chi_sq`,
+/// computed as `Q(df / 2, chi_sq / 2)` (or [chi_square_survival_wh] if `df` is greater than [WILSON_HILFERTY_MIN_DF]).
+/// returns NaN if `chi_sq` is NaN or `df` is zero.
+#[inline(always)]
+pub const fn chi_square_survival(chi_sq: Dec, df: u64) -> Dec {
+    if chi_sq.is_nan() || df == 0 {
+        return Dec::NAN;
+    }
+    if chi_sq.le(&ZERO) {
+        return ONE;
+    }
+    if df > WILSON_HILFERTY_MIN_DF {
+        return chi_square_survival_wh(chi_sq, df);
+    }
+    gamma_q(Dec::from_u64(df).mul(HALF_ONE), chi_sq.mul(HALF_ONE))
+}
+
+/// survival function of the chi-square distribution by the Wilson-Hilferty approximation.
+///
+/// `(chi_sq / df)^(1/3)` is about normal with mean `1 - 2 / (9 * df)` and variance `2 / (9 * df)`.
+/// returns NaN if `chi_sq` is NaN or `df` is zero.
+pub const fn chi_square_survival_wh(chi_sq: Dec, df: u64) -> Dec {
+    if chi_sq.is_nan() || df == 0 {
+        return Dec::NAN;
+    }
+    let k = Dec::from_u64(df);
+    let v = TWO.div(dec!(9).mul(k));
+    let z = chi_sq.div(k).cbrt().sub(ONE.sub(v)).div(v.sqrt());
+    let p = normal_p_two_sided(z).mul(HALF_ONE);
+    if z.gt(&ZERO) { p } else { ONE.sub(p) }
+}
+
+/// Compute X^2 statistic of `bins` possible values from the sum of squared counts, for sparse counts.
+///
+/// that is `sum((o - e)^2 / e) = bins / total * sum(o^2) - total`, same as [chi_statistic_bins] that counts zero for unobserved values.
+/// returns NaN if `total_buckets` is zero.
+#[inline(always)]
+pub const fn chi_statistic_sum_squares(sum_squares: Dec, bins: Dec, total_buckets: u64) -> Dec {
+    if total_buckets == 0 {
+        return Dec::NAN;
+    }
+    let total = Dec::from_u64(total_buckets);
+    bins.div(total).mul(sum_squares).sub(total)
+}
+
+/// z-score of the two-sided 95% confidence level (`1.959963984540054`).
+pub const NORMAL_Z_95: Dec = dec!(1.959963984540054);
+
+/// z-score of the two-sided 99% confidence level (`2.5758293035489004`).
+pub const NORMAL_Z_99: Dec = dec!(2.5758293035489004);
+
+/// two-sided p-value of a standard normal z-score, that is `P(|Z| >= |z|)`.
+///
+/// computed as `Q(1/2, z^2 / 2)` (same as `erfc(|z| / sqrt(2))`). returns NaN if `z` is NaN.
+#[inline(always)]
+pub const fn normal_p_two_sided(z: Dec) -> Dec {
+    if z.is_nan() {
+        return Dec::NAN;
+    }
+    gamma_q(HALF_ONE, z.mul(z).mul(HALF_ONE))
+}
+
+/// probability of a random dataset exceeds `chi_sq` with `df` degrees of freedom, same as `pochisq(chi_sq, df)` of `ent`.
+///
+/// `df` should be odd (`255` for bytes, `1` for bits), this is [probability_chi_sq] of `(df - 1) / 2`.
+#[inline(always)]
+pub const fn ent_probability_chi_sq(chi_sq: Dec, df: u16) -> Dec {
+    probability_chi_sq(chi_sq, (df - 1) / 2)
+}
+
 /// Adapted from <https://www.fourmilab.ch/random/>
 /// which is an adaption from
 ///
+/// this is the approximation used by `ent`, and only supports odd degrees of freedom (`2 * df + 1`),
+/// see [ent_probability_chi_sq] that takes the degrees of freedom. use [chi_square_survival] for even degrees of freedom.
+///
 /// ALGORITHM Compute probability of chi square value.
 ///     Adapted from:
 ///         Hill, I.D. and Pike, M.C. Algorithm 299
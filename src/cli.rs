@@ -33,19 +33,56 @@ extern crate std;
 use std::{
     io::Read,
     path::{Path, PathBuf},
+    process::ExitCode,
 };
 
 #[cfg(doc)]
 use crate as entest;
 
-use entest::{Entest, EntestResult};
+use entest::{AutocorrelationCalculation, BlockEntropyCalculation, CompressionCalculation, ChiSquareCalculation, SymbolCounts, SymbolResult, BitOrder, BuildInfo, ByteRange, Dec, Entest, EntestResult, FoldCase, Mode, MonteCarloCalculation, MonteCarloGeometry, Thresholds, transform::TransformReader};
 
-use clap::{Parser, CommandFactory};
+use clap::{Parser, CommandFactory, ValueEnum};
+
+/// bit order used by `--bits`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum BitOrderArg {
+    /// most significant bit first (same as ent).
+    Msb,
+
+    /// least significant bit first.
+    Lsb,
+}
+
+impl From<BitOrderArg> for BitOrder {
+    fn from(arg: BitOrderArg) -> Self {
+        match arg {
+            BitOrderArg::Msb => BitOrder::MsbFirst,
+            BitOrderArg::Lsb => BitOrder::LsbFirst,
+        }
+    }
+}
+
+/// output format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// human readable text.
+    Human,
+
+    /// human readable text, byte-for-byte the same as `ent`.
+    Ent,
+
+    /// CSV output of `ent -t`.
+    Terse,
+
+    /// one JSON object per input.
+    #[cfg(feature="json")]
+    Json,
+}
 
 #[derive(Debug, Parser)]
 #[command(name="entest", version, author, about="entest (entropy test) is a program that applies tests to byte sequences stored in files or streams. A rust implementation similar to ent tool: https://www.fourmilab.ch/random/")]
 pub struct Opt {
-    /// show build info. [TODO]
+    /// Show build info (version, features, decimal precision and constants).
     #[arg(long, short='i')]
     info: bool,
 
@@ -53,6 +90,18 @@ pub struct Opt {
     #[arg(long, short='b')]
     bits: bool,
 
+    /// Bit order of each byte, used with `--bits`, `--symbol-bits` or `--compression-estimate`.
+    ///
+    /// with `--symbol-bits`, `msb` means big-endian symbols and `lsb` means little-endian symbols.
+    #[arg(long, value_enum, default_value_t=BitOrderArg::Msb)]
+    bit_order: BitOrderArg,
+
+    /// Also compute entropy, chi-square and mean of BITS wide symbols (1 to 32, such as 4, 12 or 16).
+    ///
+    /// symbols up to 16 bits are counted in a table, wider symbols are counted in a sparse map.
+    #[arg(long, value_name="BITS", value_parser=clap::value_parser!(u32).range(1..=i64::from(entest::symbol::MAX_SYMBOL_BITS)))]
+    symbol_bits: Option<u32>,
+
     /// Print occurrence counts.
     #[arg(long, short='c')]
     counts: bool,
@@ -61,75 +110,669 @@ pub struct Opt {
     #[arg(long, short='f')]
     fold: bool,
 
-    /// Terse output in CSV format.
-    #[arg(long, short='t')]
+    /// Terse output in CSV format (same as `--format terse`).
+    #[cfg_attr(feature="json", arg(long, short='t', conflicts_with_all=["json", "format"]))]
+    #[cfg_attr(not(feature="json"), arg(long, short='t', conflicts_with="format"))]
     terse: bool,
 
+    /// JSON output, one object per line for each input (same as `--format json`).
+    #[cfg(feature="json")]
+    #[arg(long, conflicts_with="format")]
+    json: bool,
+
+    /// Output format.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
     /// Print this message.
     #[arg(long, short='u')]
     usage: bool,
 
-    /// optional file name.
+    /// Read more file names from FILE (`-` for standard input), one name per line.
+    #[arg(long, value_name="FILE")]
+    files_from: Option<PathBuf>,
+
+    /// File names read by `--files-from` are separated by NUL instead of newline.
+    #[arg(long, short='0', requires="files_from")]
+    null: bool,
+
+    /// Also report the result of all input bytes together, including the extra tests such as `--lags`.
+    #[arg(long, short='a')]
+    aggregate: bool,
+
+    /// Start at byte N of each input, bytes before it are skipped (seek if possible, otherwise discard).
+    ///
+    /// N accepts `0x` for hexadecimal and `K`, `M`, `G` (or `KiB`, `MiB`, `GiB`) for multiples of 1024.
+    #[arg(long, visible_alias="skip", value_name="N", value_parser=parse_size, default_value_t=0, help_heading="Range")]
+    offset: u64,
+
+    /// Analyze at most N bytes of each input, starting at `--offset`.
+    #[arg(long, value_name="N", value_parser=parse_size, help_heading="Range")]
+    length: Option<u64>,
+
+    /// Also compute the autocorrelation at each lag of LIST, such as `1..=16` or `1,2,4,8,16` (at most 64 lags, up to 1024).
+    #[arg(long, value_name="LIST", value_parser=parse_lags)]
+    lags: Option<Lags>,
+
+    /// Also compute the entropy of overlapping blocks of N samples, for N in RANGE (such as `4` for `1..=4`, or `2..=8`).
     ///
-    /// if not provided, then read from standard input.
-    file: Option<PathBuf>,
+    /// blocks are at most 8 bytes (or 64 bits with `--bits`).
+    #[arg(long, value_name="RANGE", value_parser=parse_block_range)]
+    block_entropy: Option<(u32, u32)>,
+
+    /// Also compute the compression estimate of min-entropy on the bit stream (NIST SP 800-90B section 6.3.4).
+    ///
+    /// unlike "Optimum compression", it finds repeated structure of 6-bit blocks.
+    #[arg(long)]
+    compression_estimate: bool,
+
+    /// Also compute monte-carlo pi with points of GEOMETRY, such as `16x3` for three 16-bit coordinates (can be repeated).
+    ///
+    /// coordinates are 8, 16, 24 or 32 bits, in 2 to 8 dimensions. `ent` uses `24x2`.
+    #[arg(long, value_name="GEOMETRY", value_parser=parse_geometry)]
+    monte_carlo: Vec<MonteCarloGeometry>,
+
+    /// Fail if shannon entropy is less than BITS (per sample).
+    #[arg(long, value_name="BITS", help_heading="Checks")]
+    min_shannon_entropy: Option<Dec>,
+
+    /// Fail if the most common value estimate of min-entropy is less than BITS (per sample).
+    #[arg(long, value_name="BITS", help_heading="Checks")]
+    min_entropy: Option<Dec>,
+
+    /// Fail if chi-square exceedance percent is outside of LOW,HIGH (for example `1,99`).
+    #[arg(long, value_name="LOW,HIGH", value_parser=parse_band, help_heading="Checks")]
+    chi_prob_band: Option<(Dec, Dec)>,
+
+    /// Fail if monte-carlo error is greater than PERCENT.
+    #[arg(long, value_name="PERCENT", help_heading="Checks")]
+    max_mc_error: Option<Dec>,
+
+    /// Fail if absolute serial correlation coefficient is greater than ABS.
+    #[arg(long, value_name="ABS", help_heading="Checks")]
+    max_serial_correlation: Option<Dec>,
+
+    /// optional file names. `-` means standard input.
+    ///
+    /// if not provided (and no `--files-from`), then read from standard input.
+    files: Vec<PathBuf>,
 }
 
-#[inline(always)]
-fn from_reader<R: Read>(reader: &mut R) -> std::io::Result<EntestResult> {
-    let mut buf = [0u8; 8192];
-    let mut entest = Entest::new();
-    loop {
-        let len = reader.read(&mut buf)?;
-        if len == 0 {
-            // EOF?
-            break;
+/// exit code if any input can not be read.
+const EXIT_IO_ERROR: u8 = 1;
+
+/// exit code if any result fails a check.
+const EXIT_CHECK_FAILED: u8 = 3;
+
+/// parse `LOW,HIGH`.
+fn parse_band(s: &str) -> Result<(Dec, Dec), String> {
+    let (low, high) = s.split_once(',').ok_or("expected LOW,HIGH")?;
+    let low: Dec = low.trim().parse().map_err(|err| { format!("LOW: {err}") })?;
+    let high: Dec = high.trim().parse().map_err(|err| { format!("HIGH: {err}") })?;
+    if low.gt(&high) {
+        return Err("LOW is greater than HIGH".into());
+    }
+    Ok((low, high))
+}
+
+/// lags used by `--lags`.
+#[derive(Debug, Clone)]
+struct Lags(Vec<u32>);
+
+/// parse a list of lags, such as `1..=16`, `1..16` or `1,2,4,8`.
+fn parse_lags(s: &str) -> Result<Lags, String> {
+    let mut lags = Vec::new();
+    for item in s.split(',') {
+        let item = item.trim();
+        if let Some((first, last)) = item.split_once("..") {
+            let (last, inclusive) =
+                match last.strip_prefix('=') {
+                    Some(last) => (last, true),
+                    None => (last, false),
+                };
+            let first: u32 = first.trim().parse().map_err(|err| { format!("{item}: {err}") })?;
+            let last: u32 = last.trim().parse().map_err(|err| { format!("{item}: {err}") })?;
+            if inclusive {
+                lags.extend(first..=last);
+            } else {
+                lags.extend(first..last);
+            }
+        } else {
+            lags.push(item.parse().map_err(|err| { format!("{item}: {err}") })?);
+        }
+    }
+    if lags.is_empty() || ! AutocorrelationCalculation::is_valid_lags(&lags) {
+        return Err(format!("expected 1 to {} lags, each in 1..={}", entest::autocorr::MAX_LAGS, entest::autocorr::MAX_LAG));
+    }
+    Ok(Lags(lags))
+}
+
+/// parse a range of block lengths, such as `4` (same as `1..=4`), `2..=8` or `2..9`.
+fn parse_block_range(s: &str) -> Result<(u32, u32), String> {
+    let s = s.trim();
+    let (first, last) =
+        if let Some((first, last)) = s.split_once("..") {
+            let first: u32 = first.trim().parse().map_err(|err| { format!("{s}: {err}") })?;
+            let (last, exclusive) =
+                match last.strip_prefix('=') {
+                    Some(last) => (last, false),
+                    None => (last, true),
+                };
+            let last: u32 = last.trim().parse().map_err(|err| { format!("{s}: {err}") })?;
+            (first, if exclusive { last.saturating_sub(1) } else { last })
+        } else {
+            (1, s.parse().map_err(|err| { format!("{s}: {err}") })?)
+        };
+    if first == 0 || first > last {
+        return Err("expected a non-empty range of block lengths starting from 1".into());
+    }
+    Ok((first, last))
+}
+
+/// parse `BITSxDIMENSIONS` of monte-carlo geometry.
+fn parse_geometry(s: &str) -> Result<MonteCarloGeometry, String> {
+    let (bits, dimensions) = s.split_once(['x', 'X']).ok_or("expected BITSxDIMENSIONS")?;
+    let bits: u32 = bits.trim().parse().map_err(|err| { format!("BITS: {err}") })?;
+    let dimensions: u32 = dimensions.trim().parse().map_err(|err| { format!("DIMENSIONS: {err}") })?;
+    if ! MonteCarloGeometry::is_valid(bits, dimensions) {
+        return Err(format!("expected BITS of 8, 16, 24 or 32, and DIMENSIONS in 2..={}", entest::mc::MAX_DIMENSIONS));
+    }
+    Ok(MonteCarloGeometry::new(bits, dimensions))
+}
+
+/// parse a byte size, such as `512`, `0x200`, `4K` or `16MiB`.
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (digits, unit) = s.split_at(s.find(|c: char| { c.is_ascii_alphabetic() && c != 'x' && c != 'X' }).unwrap_or(s.len()));
+    let unit: u64 =
+        match unit.to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "ki" | "kib" => 1 << 10,
+            "m" | "mi" | "mib" => 1 << 20,
+            "g" | "gi" | "gib" => 1 << 30,
+            _ => {
+                return Err(format!("unknown unit {unit:?}"));
+            },
+        };
+    let n =
+        if let Some(hex) = digits.strip_prefix("0x").or_else(|| { digits.strip_prefix("0X") }) {
+            u64::from_str_radix(hex, 16)
+        } else {
+            digits.parse()
+        }.map_err(|err| { err.to_string() })?;
+    n.checked_mul(unit).ok_or_else(|| { "too large".to_string() })
+}
+
+impl Opt {
+    /// byte range selected by command line.
+    fn range(&self) -> ByteRange {
+        ByteRange::new(self.offset, self.length)
+    }
+
+    /// thresholds selected by command line.
+    fn thresholds(&self) -> Thresholds {
+        let mut thresholds = Thresholds::new();
+        thresholds.min_shannon_entropy = self.min_shannon_entropy;
+        thresholds.min_entropy = self.min_entropy;
+        thresholds.chi_prob_band = self.chi_prob_band;
+        thresholds.max_mc_error = self.max_mc_error;
+        thresholds.max_abs_sc = self.max_serial_correlation;
+        thresholds
+    }
+
+    /// sampling mode selected by command line.
+    fn mode(&self) -> Mode {
+        if self.bits {
+            Mode::Bits(self.bit_order.into())
+        } else {
+            Mode::Bytes
+        }
+    }
+
+    /// output format selected by command line.
+    fn format(&self) -> Format {
+        #[cfg(feature="json")]
+        if self.json {
+            return Format::Json;
+        }
+        if let Some(format) = self.format {
+            format
+        } else if self.terse {
+            Format::Terse
+        } else {
+            Format::Human
+        }
+    }
+
+    /// autocorrelation state selected by command line.
+    fn autocorr(&self) -> Option<AutocorrelationCalculation> {
+        self.lags.as_ref().map(|lags| { AutocorrelationCalculation::with_mode(self.mode(), &lags.0) })
+    }
+
+    /// symbol state selected by command line.
+    fn symbols(&self) -> Option<ChiSquareCalculation<SymbolCounts>> {
+        self.symbol_bits.map(|bits| { ChiSquareCalculation::with_symbols(bits, self.bit_order.into()) })
+    }
+
+    /// block entropy state selected by command line.
+    fn block_entropy(&self) -> Option<BlockEntropyCalculation> {
+        self.block_entropy.map(|(first, last)| { BlockEntropyCalculation::with_range(self.mode(), first, last) })
+    }
+
+    /// compression estimate state selected by command line.
+    fn compression(&self) -> Option<CompressionCalculation> {
+        self.compression_estimate.then(|| { CompressionCalculation::with_order(self.bit_order.into()) })
+    }
+
+    /// extra monte-carlo states selected by command line.
+    fn monte_carlo(&self) -> Vec<MonteCarloCalculation> {
+        self.monte_carlo.iter().map(|&geometry| { MonteCarloCalculation::with_mode(self.mode()).with_geometry(geometry) }).collect()
+    }
+
+    /// all extra states selected by command line.
+    fn extras(&self) -> Extras {
+        Extras {
+            autocorr: self.autocorr(),
+            monte_carlo: self.monte_carlo(),
+            symbols: self.symbols(),
+            block_entropy: self.block_entropy(),
+            compression: self.compression(),
+        }
+    }
+
+    /// input transform selected by command line.
+    fn transform(&self) -> Option<FoldCase> {
+        if self.fold {
+            Some(FoldCase::Iso8859_1)
+        } else {
+            None
         }
+    }
+}
 
-        entest.update(&buf[..len]);
+/// the extra states selected by command line, that are updated together with [Entest].
+struct Extras {
+    autocorr: Option<AutocorrelationCalculation>,
+    monte_carlo: Vec<MonteCarloCalculation>,
+    symbols: Option<ChiSquareCalculation<SymbolCounts>>,
+    block_entropy: Option<BlockEntropyCalculation>,
+    compression: Option<CompressionCalculation>,
+}
+
+impl Extras {
+    /// apply byte stream to every extra state.
+    fn update(&mut self, bytes: &[u8]) {
+        if let Some(ref mut autocorr) = self.autocorr {
+            autocorr.update(bytes);
+        }
+        for mc in self.monte_carlo.iter_mut() {
+            mc.update(bytes);
+        }
+        if let Some(ref mut symbols) = self.symbols {
+            symbols.update(bytes);
+        }
+        if let Some(ref mut block_entropy) = self.block_entropy {
+            block_entropy.update(bytes);
+        }
+        if let Some(ref mut compression) = self.compression {
+            compression.update(bytes);
+        }
     }
+}
 
-    Ok(entest.finalize())
+/// the states of all input bytes together (`--aggregate`).
+struct Aggregate {
+    entest: Entest,
+    extras: Extras,
 }
 
+/// Tee also provides every byte read from the inner reader to the aggregate and extra states.
+struct Tee<'a, R> {
+    inner: R,
+    aggregate: Option<&'a mut Aggregate>,
+    extras: &'a mut Extras,
+}
+
+impl<R: Read> Read for Tee<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if let Some(ref mut aggregate) = self.aggregate {
+            aggregate.entest.update(&buf[..len]);
+            aggregate.extras.update(&buf[..len]);
+        }
+        self.extras.update(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// the path that means standard input.
+const STDIN: &str = "-";
+
+/// the result of one input, and the extra states selected by command line.
+struct Report {
+    result: EntestResult,
+    autocorr: Option<AutocorrelationCalculation>,
+    monte_carlo: Vec<MonteCarloCalculation>,
+    symbols: Option<SymbolResult>,
+    block_entropy: Option<BlockEntropyCalculation>,
+    compression: Option<CompressionCalculation>,
+}
+
+impl Report {
+    /// the report of `result` and the extra states of the same input.
+    fn new(result: EntestResult, extras: Extras) -> Self {
+        let Extras { autocorr, monte_carlo, symbols, block_entropy, compression } = extras;
+        let symbols = symbols.map(SymbolResult::new);
+        Self { result, autocorr, monte_carlo, symbols, block_entropy, compression }
+    }
+}
+
+/// test `reader` that is already moved to the start of `--offset`.
 #[inline(always)]
-fn from_stdin() -> std::io::Result<EntestResult> {
-    let stdin = std::io::stdin();
-    let mut stdin = std::io::BufReader::new(stdin.lock());
-    from_reader(&mut stdin)
+fn from_reader<R: Read>(reader: R, opt: &Opt, aggregate: Option<&mut Aggregate>) -> std::io::Result<Report> {
+    let range = opt.range();
+    let mut extras = opt.extras();
+    let reader = TransformReader::new(range.limit(reader), opt.transform());
+    let mut reader =
+        Tee {
+            inner: reader,
+            aggregate,
+            extras: &mut extras,
+        };
+    let result = Entest::with_mode(opt.mode()).set_offset(range.offset).update_reader(&mut reader)?.finalize();
+    Ok(Report::new(result, extras))
 }
 
 #[inline(always)]
-fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<EntestResult> {
-    let file = std::fs::File::open(path)?;
-    let mut file = std::io::BufReader::new(file);
-    from_reader(&mut file)
+fn from_path(path: &Path, opt: &Opt, aggregate: Option<&mut Aggregate>) -> std::io::Result<Report> {
+    if path.as_os_str() == STDIN {
+        let stdin = std::io::stdin();
+        let mut stdin = std::io::BufReader::new(stdin.lock());
+        opt.range().skip(&mut stdin)?;
+        from_reader(stdin, opt, aggregate)
+    } else {
+        let mut file = std::fs::File::open(path)?;
+        opt.range().seek(&mut file)?;
+        let file = std::io::BufReader::new(file);
+        from_reader(file, opt, aggregate)
+    }
+}
+
+/// read file names from `path`, separated by newline (or NUL).
+fn read_files_from(path: &Path, null: bool) -> std::io::Result<Vec<PathBuf>> {
+    let list =
+        if path.as_os_str() == STDIN {
+            let mut list = Vec::new();
+            std::io::stdin().lock().read_to_end(&mut list)?;
+            list
+        } else {
+            std::fs::read(path)?
+        };
+
+    let sep = if null { b'\0' } else { b'\n' };
+    let mut files = Vec::new();
+    for name in list.split(|b| { *b == sep }) {
+        if name.is_empty() {
+            continue;
+        }
+
+        #[cfg(unix)]
+        let name = {
+            use std::os::unix::ffi::OsStrExt;
+            PathBuf::from(std::ffi::OsStr::from_bytes(name))
+        };
+        #[cfg(not(unix))]
+        let name = PathBuf::from(String::from_utf8_lossy(name).into_owned());
+
+        files.push(name);
+    }
+    Ok(files)
+}
+
+/// JSON object of one result.
+#[cfg(feature="json")]
+fn to_json(report: &Report, name: &str, opt: &Opt) -> serde_json::Value {
+    let er = &report.result;
+    let mut doc = serde_json::json!({
+        "file": name,
+        "result": er,
+    });
+
+    if opt.counts {
+        let hist = er.histogram();
+        let occurrences: Vec<serde_json::Value> =
+            hist.iter()
+            .filter(|(_, count)| { *count > 0 })
+            .map(|(value, count)| {
+                serde_json::json!({
+                    "value": value,
+                    "occurrences": count,
+                    "fraction": hist.fraction(value),
+                })
+            })
+            .collect();
+        doc["occurrences"] = occurrences.into();
+    }
+    if let Some(ref symbols) = report.symbols {
+        doc["symbols"] = serde_json::json!({
+            "bits": symbols.bits(),
+            "bins": symbols.bins(),
+            "samples": symbols.samples(),
+            "observed": symbols.observed(),
+            "shannon": symbols.shannon(),
+            "chi": symbols.chi(),
+            "chi_prob": symbols.chi_prob(),
+            "mean": symbols.mean(),
+            "mean_z_score": symbols.mean_z_score(),
+            "mean_p_value": symbols.mean_p_value(),
+        });
+    }
+    if let Some(ref block_entropy) = report.block_entropy {
+        let blocks: Vec<serde_json::Value> =
+            block_entropy.lengths()
+            .map(|n| {
+                serde_json::json!({
+                    "length": n,
+                    "blocks": block_entropy.blocks(n),
+                    "entropy": block_entropy.block_entropy(n),
+                    "per_sample": block_entropy.per_sample(n),
+                    "rate": block_entropy.rate(n),
+                })
+            })
+            .collect();
+        doc["block_entropy"] = blocks.into();
+    }
+    if let Some(ref compression) = report.compression {
+        doc["compression_estimate"] = serde_json::json!({
+            "samples": compression.samples(),
+            "blocks": compression.blocks(),
+            "mean": compression.mean(),
+            "std_dev": compression.std_dev(),
+            "probability": compression.probability(),
+            "min_entropy": compression.finalize(),
+        });
+    }
+    if ! report.monte_carlo.is_empty() {
+        let monte_carlo: Vec<serde_json::Value> =
+            report.monte_carlo.iter()
+            .map(|mc| {
+                serde_json::json!({
+                    "geometry": mc.geometry(),
+                    "tries": mc.tries(),
+                    "in_count": mc.in_count(),
+                    "mc": mc.finalize(),
+                    "mc_error": mc.error(),
+                    "z_score": mc.z_score(),
+                    "p_value": mc.p_value(),
+                })
+            })
+            .collect();
+        doc["monte_carlo"] = monte_carlo.into();
+    }
+    if let Some(ref autocorr) = report.autocorr {
+        let lags: Vec<serde_json::Value> =
+            autocorr.outcomes()
+            .enumerate()
+            .map(|(index, (lag, outcome))| {
+                serde_json::json!({
+                    "lag": lag,
+                    "coefficient": outcome.statistic,
+                    "z_score": autocorr.z_score(index),
+                    "p_value": outcome.p_value,
+                })
+            })
+            .collect();
+        doc["autocorrelation"] = lags.into();
+    }
+    if opt.info {
+        doc["build"] = serde_json::json!(BuildInfo::CURRENT);
+    }
+
+    let thresholds = opt.thresholds();
+    if thresholds.is_enabled() {
+        let verdict = thresholds.check(er);
+        let failures: Vec<String> = verdict.failures().map(|failure| { failure.to_string() }).collect();
+        doc["pass"] = verdict.is_pass().into();
+        doc["failures"] = failures.into();
+    }
+
+    doc
+}
+
+/// print one result in the selected output format.
+///
+/// returns false if it fails any check.
+fn print_result(report: &Report, name: &str, opt: &Opt, many: bool, first: bool) -> bool {
+    let er = &report.result;
+    match opt.format() {
+        Format::Human => {
+            if many {
+                println!("==> {name} <==");
+            }
+            if ! opt.range().is_all() {
+                println!("Analyzed bytes {} ({} bytes).", er.range(), er.range().length.unwrap_or(0));
+            }
+            if opt.counts {
+                print!("{}", er.histogram());
+            }
+            println!("{er}");
+            if let Some(ref symbols) = report.symbols {
+                println!("{symbols}");
+            }
+            if let Some(ref block_entropy) = report.block_entropy {
+                println!("{block_entropy}");
+            }
+            if let Some(ref compression) = report.compression {
+                println!("{compression}");
+            }
+            for mc in report.monte_carlo.iter() {
+                println!("{mc}");
+            }
+            if let Some(ref autocorr) = report.autocorr {
+                println!("{autocorr}");
+            }
+        },
+        Format::Ent => {
+            if many {
+                println!("==> {name} <==");
+            }
+            let stdout = std::io::stdout();
+            let mut stdout = stdout.lock();
+            if let Err(err) = er.ent(opt.counts).write_to(&mut stdout) {
+                eprintln!("entest: {name}: {err}");
+            }
+        },
+        Format::Terse => {
+            let terse = er.terse(opt.counts).with_header(first);
+            if many {
+                print!("{}", terse.with_name(name));
+            } else {
+                print!("{terse}");
+            }
+        },
+        #[cfg(feature="json")]
+        Format::Json => {
+            println!("{}", to_json(report, name, opt));
+        },
+    }
+
+    let verdict = opt.thresholds().check(er);
+    for failure in verdict.failures() {
+        eprintln!("entest: {name}: FAIL: {failure}");
+    }
+    verdict.is_pass()
 }
 
 #[inline(always)]
-fn result_main() -> std::io::Result<()> {
+fn result_main() -> std::io::Result<ExitCode> {
     let opt = Opt::parse();
     if opt.info {
-        eprintln!("(LOG_SQRT_PI = {})", entest::chisqr::LOG_SQRT_PI);
-        eprintln!("(I_SQRT_PI = {})", entest::chisqr::I_SQRT_PI);
+        eprint!("{}", BuildInfo::CURRENT);
     }
     if opt.usage {
         Opt::command().print_help()?;
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some((first, last)) = opt.block_entropy {
+        if ! BlockEntropyCalculation::is_valid(opt.mode(), first, last) {
+            let max = entest::block::MAX_BLOCK_BITS / opt.mode().sample_bits();
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("--block-entropy: block length is at most {max} {}s", opt.mode().unit())));
+        }
     }
 
-    let er =
-        if let Some(file) = opt.file {
-            from_file(file)?
-        } else {
-            from_stdin()?
-        };
-    println!("{er}");
+    let mut files = opt.files.clone();
+    if let Some(ref files_from) = opt.files_from {
+        if files_from.as_os_str() == STDIN && files.iter().any(|file| { file.as_os_str() == STDIN }) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "standard input is already used by --files-from"));
+        }
+        files.extend(read_files_from(files_from, opt.null)?);
+    } else if files.is_empty() {
+        files.push(PathBuf::from(STDIN));
+    }
 
-    Ok(())
+    let many = files.len() > 1 || opt.aggregate;
+    let mut aggregate = if opt.aggregate { Some(Aggregate { entest: Entest::with_mode(opt.mode()), extras: opt.extras() }) } else { None };
+
+    let mut io_error = false;
+    let mut check_failed = false;
+    let mut first = true;
+    for file in files.iter() {
+        let name = file.to_string_lossy();
+        match from_path(file, &opt, aggregate.as_mut()) {
+            Ok(report) => {
+                check_failed |= ! print_result(&report, &name, &opt, many, first);
+                first = false;
+            },
+            Err(err) => {
+                eprintln!("entest: {name}: {err}");
+                io_error = true;
+            },
+        }
+    }
+
+    if let Some(mut aggregate) = aggregate {
+        let report = Report::new(aggregate.entest.finalize(), aggregate.extras);
+        check_failed |= ! print_result(&report, "(aggregate)", &opt, many, first);
+    }
+
+    Ok(
+        if io_error {
+            ExitCode::from(EXIT_IO_ERROR)
+        } else if check_failed {
+            ExitCode::from(EXIT_CHECK_FAILED)
+        } else {
+            ExitCode::SUCCESS
+        }
+    )
 }
 
 #[inline(always)]
-fn main() {
-    result_main().unwrap()
+fn main() -> ExitCode {
+    match result_main() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("entest: {err}");
+            ExitCode::from(EXIT_IO_ERROR)
+        },
+    }
 }
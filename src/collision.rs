@@ -0,0 +1,275 @@
+//! the collision estimate of min-entropy (NIST SP 800-90B section 6.3.2).
+
+use super::*;
+
+/// iterations of the binary search in [collision_probability], enough for the precision of [Dec].
+const SEARCH_ITER: usize = 128;
+
+/// `F(q) = Γ(k + 1, 1/q) * (1/q)^(-k-1) * e^(1/q)` of SP 800-90B, for `k` possible sample values.
+///
+/// for integer `k`, that is `q * sum(k! / (k - j)! * q^j)` over `j` in `0..=k`, so it is computed without gamma function.
+const fn collision_f(q: Dec, k: u64) -> Dec {
+    let mut sum = Dec::ZERO;
+    let mut term = Dec::ONE;
+    let mut j = 0;
+    while j <= k {
+        sum = sum.add(term);
+        term = term.mul(Dec::from_u64(k - j)).mul(q);
+        j += 1;
+    }
+    q.mul(sum)
+}
+
+/// the expected number of samples until the first collision (repeated value), for `k` possible sample values,
+/// where one value has probability `p` and the others share `1 - p` equally.
+///
+/// this is the left side of the equation in SP 800-90B, that is decreasing from `p = 1 / k` to `p = 1` (where it is 2).
+/// `p` must be in `1/k .. 1` (not including 1).
+pub const fn collision_expected_time(p: Dec, k: u64) -> Dec {
+    let kd = Dec::from_u64(k);
+    let q = Dec::ONE.sub(p).div(Dec::from_u64(k - 1));
+    let d = Dec::ONE.div(p).sub(Dec::ONE.div(q)).div(kd);
+    let f = collision_f(q, k);
+    p.div(q.mul(q)).mul(Dec::ONE.add(d)).mul(f).sub(p.div(q).mul(d))
+}
+
+/// solve [collision_expected_time] `= mean` for `p` in `1/k ..= 1` by binary search.
+///
+/// returns `1 / k` if `mean` is not less than the expected time of uniform samples, 1 if `mean` is not greater than 2,
+/// and NaN if `mean` is NaN.
+pub const fn collision_probability(mean: Dec, k: u64) -> Dec {
+    if mean.is_nan() {
+        return Dec::NAN;
+    }
+    if ! mean.gt(&chisqr::TWO) {
+        return Dec::ONE;
+    }
+    let mut low = Dec::ONE.div(Dec::from_u64(k));
+    let mut high = Dec::ONE;
+    if ! mean.lt(&collision_expected_time(low, k)) {
+        return low;
+    }
+
+    let mut i = 0;
+    while i < SEARCH_ITER {
+        let mid = low.add(high).div(chisqr::TWO);
+        if mid.eq(&low) || mid.eq(&high) {
+            break;
+        }
+        if collision_expected_time(mid, k).gt(&mean) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        i += 1;
+    }
+    low.add(high).div(chisqr::TWO)
+}
+
+/// Computes the collision estimate of min-entropy.
+///
+/// the samples are split into runs that end at the first repeated value (collision):
+/// for bytes, a run has 2 to 257 samples; for bits, 2 or 3 samples. the samples after the last collision are ignored.
+/// the lower confidence bound of mean run length is used to solve for the probability of the most likely value,
+/// see [collision_probability].
+///
+/// this is a conservative estimate: random bytes are about 5 bits per byte for megabytes of data.
+#[derive(Debug, Copy, Clone)]
+pub struct CollisionCalculation {
+    /// values seen in the current run (bit set)
+    seen: [u64; 4],
+    /// length of the current run
+    run: u64,
+    /// number of collisions
+    collisions: u64,
+    /// sum of run lengths
+    sum: u64,
+    /// sum of squared run lengths
+    sum_squares: u64,
+    /// total samples processed
+    total: u64,
+    /// sampling mode
+    mode: Mode,
+}
+
+impl Default for CollisionCalculation {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::INIT
+    }
+}
+
+impl CollisionCalculation {
+    /// the blanket state (initial value) of [CollisionCalculation].
+    pub const INIT: Self =
+        Self {
+            seen: [0; 4],
+            run: 0,
+            collisions: 0,
+            sum: 0,
+            sum_squares: 0,
+            total: 0,
+            mode: Mode::Bytes,
+        };
+
+    /// create new blanket state for collision estimate.
+    ///
+    /// this just copy from [CollisionCalculation::INIT].
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::INIT
+    }
+
+    /// creates new blanket state for collision estimate with the provided [Mode].
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        let mut this = Self::INIT;
+        this.mode = mode;
+        this
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// apply byte stream to collision state.
+    #[inline(always)]
+    pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        let bytes_len = bytes.len();
+
+        let mut i = 0;
+        let mut j;
+        let mut bits;
+        while i < bytes_len {
+            match self.mode {
+                Mode::Bytes => {
+                    self.push(bytes[i]);
+                },
+                Mode::Bits(order) => {
+                    bits = order.expand(bytes[i]);
+                    j = 0;
+                    while j < 8 {
+                        self.push(bits[j]);
+                        j += 1;
+                    }
+                },
+            }
+            i += 1;
+        }
+
+        self
+    }
+
+    /// apply one sample to collision state.
+    #[inline(always)]
+    const fn push(&mut self, b: u8) {
+        let word = (b >> 6) as usize;
+        let bit = 1u64 << (b & 63);
+
+        self.run += 1;
+        self.total += 1;
+        if self.seen[word] & bit != 0 {
+            self.collisions += 1;
+            self.sum += self.run;
+            self.sum_squares += self.run * self.run;
+            self.run = 0;
+            self.seen = [0; 4];
+        } else {
+            self.seen[word] |= bit;
+        }
+    }
+
+    /// get the samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total
+    }
+
+    /// the number of collisions (complete runs).
+    #[inline(always)]
+    pub const fn collisions(&self) -> u64 {
+        self.collisions
+    }
+
+    /// the mean number of samples until a collision.
+    ///
+    /// returns NaN if there is no collision.
+    pub const fn mean(&self) -> Dec {
+        if self.collisions == 0 {
+            return Dec::NAN;
+        }
+        Dec::from_u64(self.sum).div(Dec::from_u64(self.collisions))
+    }
+
+    /// the sample standard deviation of the number of samples until a collision.
+    ///
+    /// returns NaN if there are less than 2 collisions.
+    pub const fn std_dev(&self) -> Dec {
+        if self.collisions < 2 {
+            return Dec::NAN;
+        }
+        let v = Dec::from_u64(self.collisions);
+        let sum = Dec::from_u64(self.sum);
+        let var = Dec::from_u64(self.sum_squares).sub(sum.mul(sum).div(v)).div(v.sub(Dec::ONE));
+        if var.lt(&Dec::ZERO) { Dec::ZERO } else { var.sqrt() }
+    }
+
+    /// the lower confidence bound of the mean, that is `mean - Z_ALPHA * std_dev / sqrt(collisions)`.
+    pub const fn lower_mean(&self) -> Dec {
+        if self.collisions < 2 {
+            return Dec::NAN;
+        }
+        let v = Dec::from_u64(self.collisions);
+        self.mean().sub(mcv::Z_ALPHA.mul(self.std_dev()).div(v.sqrt()))
+    }
+
+    /// the estimated probability of the most likely value, see [collision_probability].
+    #[inline(always)]
+    pub const fn probability(&self) -> Dec {
+        collision_probability(self.lower_mean(), self.mode.bins() as u64)
+    }
+
+    /// get finalize min-entropy of current byte stream, in bits per sample.
+    ///
+    /// returns NaN if there are less than 2 collisions.
+    #[inline(always)]
+    pub const fn finalize(&self) -> Dec {
+        mcv::min_entropy_of(self.probability())
+    }
+
+    /// get the outcome of current state, that is expected to be (less than) [Mode::sample_bits] for random input.
+    ///
+    /// p-value is not defined for min-entropy.
+    #[inline(always)]
+    pub const fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::from_u32(self.mode.sample_bits()), self.total)
+    }
+
+    /// oneshot test function for small data.
+    ///
+    /// this is equivalent to `Self::new().update(data).finalize()`.
+    #[inline(always)]
+    pub const fn test(data: &[u8]) -> Dec {
+        let mut this = Self::INIT;
+        this.update(data);
+        this.finalize()
+    }
+}
+
+impl EntropyTest for CollisionCalculation {
+    #[inline(always)]
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes);
+    }
+
+    #[inline(always)]
+    fn finalize(&self) -> Dec {
+        Self::finalize(self)
+    }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
+}
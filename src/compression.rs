@@ -0,0 +1,384 @@
+//! the compression estimate of min-entropy for binary samples (NIST SP 800-90B section 6.3.4).
+
+use super::*;
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// bits of every block (`6`), the bit stream is split into blocks of this width.
+pub const COMPRESSION_BLOCK_BITS: u32 = 6;
+
+/// the number of blocks (`1000`) that only initialize the dictionary.
+pub const COMPRESSION_DICTIONARY_BLOCKS: u64 = 1000;
+
+/// the correction factor (`0.5907`) of the standard deviation, for 6-bit blocks.
+pub const COMPRESSION_C: Dec = dec!(0.5907);
+
+// the number of possible blocks.
+const BLOCK_VALUES: usize = 1 << COMPRESSION_BLOCK_BITS;
+
+/// iterations of the binary search in [compression_probability], enough for the precision of [Dec].
+const SEARCH_ITER: usize = 128;
+
+/// terms of [compression_g] are ignored if `(1 - z)^(u - 1)` is less than this (`1e-30`).
+const TERM_EPSILON: Dec = dec!(1e-30);
+
+/// distances up to this (`4096`) are summed one by one in [compression_g].
+const EXACT_TERMS: u64 = 4096;
+
+/// longer distances are summed in this number of chunks (`256`) for every power of two.
+const CHUNKS_PER_OCTAVE: u64 = 256;
+
+/// `log2` values used by [compression_g], these are computed once for all iterations of the binary search.
+#[derive(Debug, Clone, Default)]
+struct Log2Table {
+    /// `log2(u)` of every `u` in `1..=EXACT_TERMS`
+    exact: Vec<Dec>,
+    /// `log2` of the midpoint of every chunk
+    chunks: Vec<Dec>,
+}
+
+impl Log2Table {
+    /// `log2(u)` for `u` in `1..=EXACT_TERMS`.
+    fn exact(&mut self, u: u64) -> Dec {
+        while (self.exact.len() as u64) < u {
+            let next = Dec::from_u64(self.exact.len() as u64 + 1);
+            self.exact.push(next.log2());
+        }
+        self.exact[(u - 1) as usize]
+    }
+
+    /// `log2` of the midpoint of the `index`-th chunk, that is `u..u + width`.
+    fn chunk(&mut self, index: usize, u: u64, width: u64) -> Dec {
+        if index == self.chunks.len() {
+            // log2(u + (width - 1) / 2)
+            self.chunks.push(Dec::from_u64(2 * u + width - 1).log2().sub(Dec::ONE));
+        }
+        self.chunks[index]
+    }
+}
+
+/// `(sum(r^j), r^m)` for `j` in `0..m`, by doubling, so there is no cancellation for `r` close to 1.
+fn geometric(r: Dec, m: u64) -> (Dec, Dec) {
+    let mut sum = Dec::ZERO;
+    let mut power = Dec::ONE;
+    for bit in (0..u64::BITS - m.leading_zeros()).rev() {
+        sum = sum.mul(Dec::ONE.add(power));
+        power = power.mul(power);
+        if (m >> bit) & 1 == 1 {
+            sum = Dec::ONE.add(r.mul(sum));
+            power = power.mul(r);
+        }
+    }
+    (sum, power)
+}
+
+/// `G(z)` of SP 800-90B for `blocks` blocks, that is the expected `log2` of distance
+/// of a block that has probability `z`, averaged over the test blocks.
+///
+/// the double sum is rearranged into a single sum over the distance `u`:
+/// `z^2 * sum(log2(u) * (1 - z)^(u - 1) * (blocks - max(u, d)))` over `u < blocks`,
+/// plus `z * sum(log2(t) * (1 - z)^(t - 1))` over `t` in `d + 1 ..= blocks`, divided by the number of test blocks.
+///
+/// distances longer than [EXACT_TERMS] only matter for small `z`. these are summed in chunks of `2^-8` of the distance,
+/// with `log2` and the weight at the midpoint of every chunk (the relative error of this is less than `1e-6`).
+fn compression_g(z: Dec, blocks: u64, logs: &mut Log2Table) -> Dec {
+    let d = COMPRESSION_DICTIONARY_BLOCKS;
+    let r = Dec::ONE.sub(z);
+
+    let mut inner = Dec::ZERO;
+    let mut last = Dec::ZERO;
+    let mut power = Dec::ONE;
+    let mut u = 1;
+    while u <= blocks && u <= EXACT_TERMS && ! power.lt(&TERM_EPSILON) {
+        let log = logs.exact(u);
+        if u < blocks {
+            inner = inner.add(log.mul(power).mul(Dec::from_u64(blocks - u.max(d))));
+        }
+        if u > d {
+            last = last.add(log.mul(power));
+        }
+        power = power.mul(r);
+        u += 1;
+    }
+
+    // every chunk is after the dictionary, and the weight `blocks - u` is zero at `u = blocks`.
+    let mut width = EXACT_TERMS / CHUNKS_PER_OCTAVE;
+    let mut index = 0;
+    while u <= blocks && ! power.lt(&TERM_EPSILON) {
+        let (mut sum, mut step) = geometric(r, width);
+        let mut c = 0;
+        while c < CHUNKS_PER_OCTAVE && u <= blocks && ! power.lt(&TERM_EPSILON) {
+            let len = width.min(blocks + 1 - u);
+            if len < width {
+                (sum, step) = geometric(r, len);
+            }
+            let log = logs.chunk(index, u, len);
+            let weight = Dec::from_u64(2 * (blocks - u) + 1 - len).div(chisqr::TWO);
+            let chunk = log.mul(power).mul(sum);
+            inner = inner.add(chunk.mul(weight));
+            last = last.add(chunk);
+
+            power = power.mul(step);
+            u += len;
+            index += 1;
+            c += 1;
+        }
+        width *= 2;
+    }
+
+    z.mul(z).mul(inner).add(z.mul(last)).div(Dec::from_u64(blocks - d))
+}
+
+/// the expected mean `log2` of distance if one block value has probability `p`
+/// and the others share `1 - p` equally, that is `G(p) + (2^6 - 1) * G(q)` for `q = (1 - p) / (2^6 - 1)`.
+///
+/// it is decreasing from `p = 2^-6` to `p = 1`.
+/// returns NaN if `blocks` is not greater than [COMPRESSION_DICTIONARY_BLOCKS] (there is no test block).
+pub fn compression_expected_mean(p: Dec, blocks: u64) -> Dec {
+    if blocks <= COMPRESSION_DICTIONARY_BLOCKS {
+        return Dec::NAN;
+    }
+    compression_expected_mean_with(p, blocks, &mut Log2Table::default())
+}
+
+fn compression_expected_mean_with(p: Dec, blocks: u64, logs: &mut Log2Table) -> Dec {
+    let others = Dec::from_usize(BLOCK_VALUES - 1);
+    let q = Dec::ONE.sub(p).div(others);
+    compression_g(p, blocks, logs).add(others.mul(compression_g(q, blocks, logs)))
+}
+
+/// solve [compression_expected_mean] `= mean` for `p` in `2^-6 ..= 1` by binary search.
+///
+/// returns `2^-6` if `mean` is not less than the expected mean of uniform blocks, 1 if `mean` is not positive,
+/// and NaN if `mean` is NaN or `blocks` is not greater than [COMPRESSION_DICTIONARY_BLOCKS].
+pub fn compression_probability(mean: Dec, blocks: u64) -> Dec {
+    if mean.is_nan() || blocks <= COMPRESSION_DICTIONARY_BLOCKS {
+        return Dec::NAN;
+    }
+    if ! mean.gt(&Dec::ZERO) {
+        return Dec::ONE;
+    }
+    let mut logs = Log2Table::default();
+    let mut low = Dec::ONE.div(Dec::from_usize(BLOCK_VALUES));
+    let mut high = Dec::ONE;
+    if ! mean.lt(&compression_expected_mean_with(low, blocks, &mut logs)) {
+        return low;
+    }
+
+    for _ in 0..SEARCH_ITER {
+        let mid = low.add(high).div(chisqr::TWO);
+        if mid.eq(&low) || mid.eq(&high) {
+            break;
+        }
+        if compression_expected_mean_with(mid, blocks, &mut logs).gt(&mean) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low.add(high).div(chisqr::TWO)
+}
+
+/// Computes the compression estimate of min-entropy on the bit stream.
+///
+/// the bits are split into 6-bit blocks. the first [COMPRESSION_DICTIONARY_BLOCKS] blocks initialize a dictionary
+/// of the last position of every block value, then for every later block, the distance to the last same block is
+/// recorded (this is the statistic of Maurer's universal test). the lower confidence bound of the mean `log2`
+/// of distances is used to solve for the probability of the most likely block, see [compression_probability].
+///
+/// unlike the order-0 estimate of [EntestResult::compression], repeated structure shortens the distances,
+/// so it is lower for sources with structure. it is conservative: random bits are about 0.8 bits per bit for 10 KiB,
+/// and about 0.9 bits per bit for megabytes.
+/// bits of the last incomplete block are ignored.
+#[derive(Debug, Clone)]
+pub struct CompressionCalculation {
+    /// the last position of every block value (1-based), zero if not seen yet
+    dictionary: [u64; BLOCK_VALUES],
+    /// number of blocks
+    blocks: u64,
+    /// occurrence counts of distances
+    distances: BTreeMap<u64, u64>,
+    /// bits of the incomplete block
+    pending: u8,
+    /// the number of bits in `pending`
+    pending_len: u32,
+    /// total bits processed
+    total: u64,
+    /// order of bits in every byte
+    order: BitOrder,
+}
+
+impl Default for CompressionCalculation {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressionCalculation {
+    /// create new blanket state for compression estimate.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_order(BitOrder::MsbFirst)
+    }
+
+    /// creates new blanket state for compression estimate with the provided [BitOrder].
+    #[inline(always)]
+    pub const fn with_order(order: BitOrder) -> Self {
+        Self {
+            dictionary: [0; BLOCK_VALUES],
+            blocks: 0,
+            distances: BTreeMap::new(),
+            pending: 0,
+            pending_len: 0,
+            total: 0,
+            order,
+        }
+    }
+
+    /// get the bit order of current state.
+    #[inline(always)]
+    pub const fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// apply byte stream to compression state.
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        for &byte in bytes {
+            for bit in self.order.expand(byte) {
+                self.pending = (self.pending << 1) | bit;
+                self.pending_len += 1;
+                self.total += 1;
+                if self.pending_len == COMPRESSION_BLOCK_BITS {
+                    self.push(self.pending as usize);
+                    self.pending = 0;
+                    self.pending_len = 0;
+                }
+            }
+        }
+        self
+    }
+
+    /// apply one block to compression state.
+    #[inline(always)]
+    fn push(&mut self, block: usize) {
+        self.blocks += 1;
+        let i = self.blocks;
+        if i > COMPRESSION_DICTIONARY_BLOCKS {
+            let last = self.dictionary[block];
+            let distance = if last == 0 { i } else { i - last };
+            *self.distances.entry(distance).or_insert(0) += 1;
+        }
+        self.dictionary[block] = i;
+    }
+
+    /// get the samples (bits) of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total
+    }
+
+    /// the number of blocks of current state.
+    #[inline(always)]
+    pub const fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// the number of test blocks, that is blocks after the dictionary initialization.
+    #[inline(always)]
+    pub const fn test_blocks(&self) -> u64 {
+        self.blocks.saturating_sub(COMPRESSION_DICTIONARY_BLOCKS)
+    }
+
+    /// sum of `log2(distance)` and sum of its squares.
+    fn log_sums(&self) -> (Dec, Dec) {
+        self.distances.iter().fold((Dec::ZERO, Dec::ZERO), |(sum, sum_squares), (&distance, &count)| {
+            let log = Dec::from_u64(distance).log2();
+            let count = Dec::from_u64(count);
+            (sum.add(log.mul(count)), sum_squares.add(log.mul(log).mul(count)))
+        })
+    }
+
+    /// the mean `log2` of distances.
+    ///
+    /// returns NaN if there is no test block.
+    pub fn mean(&self) -> Dec {
+        if self.test_blocks() == 0 {
+            return Dec::NAN;
+        }
+        self.log_sums().0.div(Dec::from_u64(self.test_blocks()))
+    }
+
+    /// the standard deviation of `log2` of distances, corrected by [COMPRESSION_C].
+    ///
+    /// returns NaN if there are less than 2 test blocks.
+    pub fn std_dev(&self) -> Dec {
+        let v = self.test_blocks();
+        if v < 2 {
+            return Dec::NAN;
+        }
+        let (sum, sum_squares) = self.log_sums();
+        let mean = sum.div(Dec::from_u64(v));
+        let var = sum_squares.div(Dec::from_u64(v - 1)).sub(mean.mul(mean));
+        if var.lt(&Dec::ZERO) { Dec::ZERO } else { COMPRESSION_C.mul(var.sqrt()) }
+    }
+
+    /// the lower confidence bound of the mean, that is `mean - Z_ALPHA * std_dev / sqrt(test_blocks)`.
+    pub fn lower_mean(&self) -> Dec {
+        let v = self.test_blocks();
+        if v < 2 {
+            return Dec::NAN;
+        }
+        self.mean().sub(mcv::Z_ALPHA.mul(self.std_dev()).div(Dec::from_u64(v).sqrt()))
+    }
+
+    /// the estimated probability of the most likely block, see [compression_probability].
+    #[inline(always)]
+    pub fn probability(&self) -> Dec {
+        compression_probability(self.lower_mean(), self.blocks)
+    }
+
+    /// get finalize min-entropy of current byte stream, in bits per bit (at most 1).
+    ///
+    /// returns NaN if there are less than 2 test blocks.
+    pub fn finalize(&self) -> Dec {
+        let entropy = mcv::min_entropy_of(self.probability());
+        if entropy.is_nan() {
+            return Dec::NAN;
+        }
+        entropy.div(Dec::from_u32(COMPRESSION_BLOCK_BITS))
+    }
+
+    /// get the outcome of current state, that is expected to be (less than) 1 for random input.
+    ///
+    /// p-value is not defined for min-entropy.
+    #[inline(always)]
+    pub fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::ONE, self.total)
+    }
+}
+
+impl EntropyTest for CompressionCalculation {
+    #[inline(always)]
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes);
+    }
+
+    #[inline(always)]
+    fn finalize(&self) -> Dec {
+        Self::finalize(self)
+    }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
+}
+
+/// the min-entropy, and how much the input could be reduced by a compression with the same estimate.
+impl core::fmt::Display for CompressionCalculation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let entropy = self.finalize();
+        let reduce = if entropy.is_nan() { Dec::NAN } else { dec!(100).mul(Dec::ONE.sub(entropy)) };
+        writeln!(f, "Compression estimate of min-entropy = {} bits per bit ({} blocks).", format::CFloat(entropy, 6), self.blocks)?;
+        writeln!(f, "Compression by this estimate would reduce the size of this {} bit file by {} percent.", self.total, format::CFloat(reduce, 2))
+    }
+}
@@ -3,10 +3,20 @@
 use crate::*;
 
 /// EntestResult is contains results from all test methods.
+///
+/// with `serde` feature, it is serialized together with derived values (such as [EntestResult::mc_error]).
+/// derived values are ignored by deserialization.
 #[derive(Copy, Clone)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(into="serde_impl::EntestResultRepr", try_from="serde_impl::EntestResultRepr"))]
 #[non_exhaustive]
 pub struct EntestResult {
-    samples: u64, // samples of input (length of bytes)
+    mode: Mode,
+    range: ByteRange, // analyzed bytes of input
+    buckets: [u64; 256], // occurrence counts
+    samples: u64, // samples of input (length of bytes, or length of bits in bit mode)
+    mc_tries: u64, // points of monte-carlo
+    mc_in_count: u64, // points of monte-carlo inside of the circle
+    mc_geometry: MonteCarloGeometry,
     chi: Dec,
     chi_prob: Dec,
     mc: Dec,
@@ -18,7 +28,12 @@ pub struct EntestResult {
 impl core::fmt::Debug for EntestResult {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("EntestResult")
+         .field("mode", &(self.mode))
+         .field("range", &(self.range))
          .field("samples", &(self.samples))
+         .field("mc_tries", &(self.mc_tries))
+         .field("mc_in_count", &(self.mc_in_count))
+         .field("mc_geometry", &(self.mc_geometry))
          .field("chi", &(self.chi.to_string()))
          .field("chi_prob", &(self.chi_prob.to_string()))
 
@@ -59,82 +74,387 @@ impl core::fmt::Display for EntestResult {
             }
         }
 
+        let mode = self.mode;
+        let unit = mode.unit();
+        let expected_mean = mode.expected_mean();
         let samples = self.samples();
 
         let chi = self.chi();
-        let chi_prob = self.chi_prob.mul(dec!(100.0));
+        let chi_prob = self.chi_prob_percent();
 
         let mc = self.mc;
-        let mc_error = error_ratio(Dec::PI, mc).mul(dec!(100.0));
+        let mc_error = self.mc_error();
+        let mc_z = format::CFloat(self.mc_z_score(), 2);
+        let mc_p = format::CFloat(self.mc_p_value(), 4);
+        let (mc_low, mc_high) = self.mc_confidence_interval(chisqr::NORMAL_Z_95);
+        let (mc_low, mc_high) = (format::CFloat(mc_low, 6), format::CFloat(mc_high, 6));
 
         let mean = self.mean();
+        let mean_z = format::CFloat(self.mean_z_score(), 2);
+        let mean_p = format::CFloat(self.mean_p_value(), 4);
         let sc = self.sc();
+        let sc_z = format::CFloat(self.sc_z_score(), 2);
+        let sc_p = format::CFloat(self.sc_p_value(), 4);
+        let (sc_low, sc_high) = self.sc_band();
+        let (sc_low, sc_high) = (format::CFloat(sc_low, 6), format::CFloat(sc_high, 6));
         let shannon = self.shannon;
+        let min_entropy = format::CFloat(self.min_entropy(), 6);
+        let mcv_p = format::CFloat(self.mcv_upper_bound(), 6);
+        let (hartley, collision, renyi_min) = self.histogram().renyi_orders();
+        let (hartley, collision, renyi_min) = (format::CFloat(hartley, 6), format::CFloat(collision, 6), format::CFloat(renyi_min, 6));
 
-        let compress_ratio = dec!(100).mul(dec!(8).sub(shannon)).div(dec!(8));
+        let compress_ratio = self.compression();
 
         f.write_str("
 Entropy = ")?;
         r!(shannon);
-        write!(f, " bits per byte.
-Optimum compression would reduce the size of this {samples} byte file by {compress_ratio:.2} percent.
+        write!(f, " bits per {unit}.
+Min-entropy = {min_entropy} bits per {unit} (most common value, probability at most {mcv_p}).
+Renyi entropy = {hartley} (order 0), {collision} (order 2), {renyi_min} (order inf) bits per {unit}.
+Optimum compression would reduce the size of this {samples} {unit} file by {compress_ratio:.2} percent.
 
 Chi square distribution for {samples} samples is ")?;
         r!(chi);
         write!(f, ",
 and randomly would exceed this value {chi_prob:.2} percent of the times.
 
-Arithmetic mean value of data bytes is ")?;
+Arithmetic mean value of data {unit}s is ")?;
         r!(mean);
-        f.write_str(" (127.5 = random).
+        write!(f, " ({expected_mean} = random).
+The deviation is {mean_z} standard errors (p-value {mean_p}).
 
 Monte Carlo value for Pi is ")?;
         r!(mc);
         write!(f, " (error {mc_error:.2} percent).
+The error is {mc_z} standard deviations (p-value {mc_p}), 95% confidence interval is {mc_low} ..= {mc_high}.
 
-(TODO scc is not accurate for small inputs)
 Serial correlation coefficient is ")?;
         r!(sc);
-        f.write_str(" (totally uncorrelated = 0.0).
+        write!(f, " (totally uncorrelated = 0.0).
+The deviation is {sc_z} standard deviations (p-value {sc_p}), expected range is {sc_low} ..= {sc_high}.
 ")
     }
 }
 
 impl EntestResult {
-    /// total samples of input. that is the length of bytes.
+    /// the sampling mode that produced this result.
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the bytes of input that produced this result.
+    ///
+    /// `length` of the range is always Some, that is the number of bytes actually analyzed.
+    pub const fn range(&self) -> ByteRange {
+        self.range
+    }
+
+    /// human readable output of `ent`, with the same wording and precision.
+    ///
+    /// if `counts` is true, the occurrence table is printed before results (this is `ent -c`).
+    pub const fn ent(&self, counts: bool) -> EntCompat<'_> {
+        EntCompat::new(self, counts)
+    }
+
+    /// CSV output of `ent -t`.
+    ///
+    /// if `counts` is true, rows of occurrence counts is also included (this is `ent -t -c`).
+    pub const fn terse(&self, counts: bool) -> Terse<'_> {
+        Terse::new(self, counts)
+    }
+
+    /// occurrence counts of input.
+    pub const fn histogram(&self) -> Histogram<'_> {
+        Histogram::new(&self.buckets, self.samples, self.mode)
+    }
+
+    /// total samples of input. that is the length of bytes (or length of bits in bit mode).
     pub const fn samples(&self) -> u64 {
         self.samples
     }
 
     /// result of chi-square calculation.
-    pub const fn chi<'a>(&'a self) -> &'a Dec {
+    pub const fn chi(&self) -> &Dec {
         &self.chi
     }
 
-    /// result of `probability_chi_sq(self.chi)`
-    pub const fn chi_prob<'a>(&'a self) -> &'a Dec {
+    /// probability of a random dataset exceeds [EntestResult::chi], by `chi_square_survival` with `bins - 1` degrees of freedom.
+    pub const fn chi_prob(&self) -> &Dec {
         &self.chi_prob
     }
 
     /// result of monte-carlo calculation.
-    pub const fn mc<'a>(&'a self) -> &'a Dec {
+    pub const fn mc(&self) -> &Dec {
         &self.mc
     }
 
+    /// result of monte-carlo calculation corrected for the lattice of coordinates, see [MonteCarloCalculation::corrected].
+    pub const fn mc_corrected(&self) -> Dec {
+        mc::corrected_pi(self.mc_in_count, self.mc_tries, self.mc_geometry)
+    }
+
     /// result of mean calculation.
-    pub const fn mean<'a>(&'a self) -> &'a Dec {
+    pub const fn mean(&self) -> &Dec {
         &self.mean
     }
 
     /// result of serial-correlation-coefficient calculation.
-    pub const fn sc<'a>(&'a self) -> &'a Dec {
+    pub const fn sc(&self) -> &Dec {
         &self.sc
     }
 
     /// result of shannon calculation.
-    pub const fn shannon<'a>(&'a self) -> &'a Dec {
+    pub const fn shannon(&self) -> &Dec {
         &self.shannon
     }
+
+    /// the upper bound of the probability of the most common sample, see [mcv::mcv_upper_bound].
+    pub const fn mcv_upper_bound(&self) -> Dec {
+        mcv::mcv_upper_bound(mcv::max_count(&self.buckets), self.samples)
+    }
+
+    /// the most common value estimate of min-entropy (NIST SP 800-90B section 6.3.1), in bits per sample.
+    ///
+    /// it is computed from the occurrence counts, see [mcv::mcv_min_entropy].
+    pub const fn min_entropy(&self) -> Dec {
+        mcv::min_entropy_of(self.mcv_upper_bound())
+    }
+
+    /// the number of points of monte-carlo calculation.
+    pub const fn mc_tries(&self) -> u64 {
+        self.mc_tries
+    }
+
+    /// standard score of the mean, see [mean::mean_z_score].
+    pub const fn mean_z_score(&self) -> Dec {
+        mean::mean_z_score(self.mean, self.samples, self.mode.bins())
+    }
+
+    /// two-sided p-value of [EntestResult::mean_z_score].
+    pub const fn mean_p_value(&self) -> Dec {
+        chisqr::normal_p_two_sided(self.mean_z_score())
+    }
+
+    /// standard score of the serial correlation coefficient, see [sc::sc_z_score].
+    pub const fn sc_z_score(&self) -> Dec {
+        sc::sc_z_score(self.sc, self.samples)
+    }
+
+    /// two-sided p-value of [EntestResult::sc_z_score].
+    pub const fn sc_p_value(&self) -> Dec {
+        chisqr::normal_p_two_sided(self.sc_z_score())
+    }
+
+    /// the range that serial correlation coefficient of random input is inside of about 95% of the times, see [sc::sc_band].
+    pub const fn sc_band(&self) -> (Dec, Dec) {
+        sc::sc_band(self.samples)
+    }
+
+    /// the number of monte-carlo points inside of the circle (or n-ball).
+    pub const fn mc_in_count(&self) -> u64 {
+        self.mc_in_count
+    }
+
+    /// the geometry of monte-carlo points.
+    pub const fn mc_geometry(&self) -> MonteCarloGeometry {
+        self.mc_geometry
+    }
+
+    /// standard score of the monte-carlo points inside of the circle, see [mc::ball_z_score].
+    pub const fn mc_z_score(&self) -> Dec {
+        mc::ball_z_score(self.mc_in_count, self.mc_tries, self.mc_geometry.probability())
+    }
+
+    /// two-sided p-value of [EntestResult::mc_z_score].
+    pub const fn mc_p_value(&self) -> Dec {
+        chisqr::normal_p_two_sided(self.mc_z_score())
+    }
+
+    /// confidence interval of the monte-carlo value, see [mc::ball_confidence_interval].
+    pub const fn mc_confidence_interval(&self, z: Dec) -> (Dec, Dec) {
+        mc::ball_confidence_interval(self.mc_in_count, self.mc_tries, z, self.mc_geometry)
+    }
+
+    /// the outcome of one test.
+    pub const fn outcome(&self, kind: TestKind) -> TestOutcome {
+        match kind {
+            TestKind::Entropy => TestOutcome::new(self.shannon, Dec::from_u32(self.mode.sample_bits()), self.samples),
+            TestKind::ChiSquare => {
+                TestOutcome::new(self.chi, Dec::from_usize(self.mode.bins() - 1), self.samples)
+                    .with_p_value(self.chi_prob)
+                    .with_both_tails(true)
+            },
+            TestKind::Mean => TestOutcome::new(self.mean, self.mode.expected_mean(), self.samples).with_p_value(self.mean_p_value()),
+            TestKind::MonteCarlo => TestOutcome::new(self.mc, Dec::PI, self.mc_tries).with_p_value(self.mc_p_value()),
+            TestKind::SerialCorrelation => {
+                TestOutcome::new(self.sc, sc::sc_expected(self.samples), self.samples)
+                    .with_p_value(self.sc_p_value())
+            },
+        }
+    }
+
+    /// the outcomes of all tests, in the order of [TestKind::ALL].
+    pub const fn outcomes(&self) -> [(TestKind, TestOutcome); 5] {
+        let mut outcomes = [(TestKind::Entropy, TestOutcome::new(Dec::NAN, Dec::NAN, 0)); 5];
+        let mut i = 0;
+        while i < TestKind::ALL.len() {
+            let kind = TestKind::ALL[i];
+            outcomes[i] = (kind, self.outcome(kind));
+            i += 1;
+        }
+        outcomes
+    }
+
+    /// percent of `chi_prob`, that is how often a truly random sequence would exceed the chi-square value.
+    pub const fn chi_prob_percent(&self) -> Dec {
+        if self.chi_prob.is_nan() {
+            return Dec::NAN;
+        }
+        self.chi_prob.mul(dec!(100.0))
+    }
+
+    /// error of the monte-carlo value, in percent of pi.
+    pub const fn mc_error(&self) -> Dec {
+        if self.mc.is_nan() {
+            return Dec::NAN;
+        }
+        error_ratio(Dec::PI, self.mc).mul(dec!(100.0))
+    }
+
+    /// the size of input could be reduced by optimum (order-0) compression, in percent.
+    pub const fn compression(&self) -> Dec {
+        if self.shannon.is_nan() {
+            return Dec::NAN;
+        }
+        let sample_bits = Dec::from_u32(self.mode.sample_bits());
+        dec!(100).mul(sample_bits.sub(self.shannon)).div(sample_bits)
+    }
+}
+
+#[cfg(feature="serde")]
+mod serde_impl {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// serialized form of [EntestResult].
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub(super) struct EntestResultRepr {
+        mode: Mode,
+        range: ByteRange,
+        samples: u64,
+        mc_tries: u64,
+        mc_in_count: u64,
+        #[serde(default)]
+        mc_geometry: MonteCarloGeometry,
+
+        shannon: Dec,
+        #[serde(skip_deserializing)]
+        compression: Dec,
+        #[serde(skip_deserializing)]
+        min_entropy: Dec,
+        #[serde(skip_deserializing)]
+        mcv_upper_bound: Dec,
+        #[serde(skip_deserializing)]
+        renyi_hartley: Dec,
+        #[serde(skip_deserializing)]
+        renyi_collision: Dec,
+        #[serde(skip_deserializing)]
+        renyi_min: Dec,
+
+        chi: Dec,
+        chi_prob: Dec,
+        #[serde(skip_deserializing)]
+        chi_prob_percent: Dec,
+
+        mean: Dec,
+        #[serde(skip_deserializing)]
+        mean_z_score: Dec,
+        #[serde(skip_deserializing)]
+        mean_p_value: Dec,
+
+        mc: Dec,
+        #[serde(skip_deserializing)]
+        mc_error: Dec,
+        #[serde(skip_deserializing)]
+        mc_z_score: Dec,
+        #[serde(skip_deserializing)]
+        mc_p_value: Dec,
+
+        sc: Dec,
+        #[serde(skip_deserializing)]
+        sc_z_score: Dec,
+        #[serde(skip_deserializing)]
+        sc_p_value: Dec,
+
+        /// occurrence counts, indexed by sample value.
+        counts: Vec<u64>,
+    }
+
+    impl From<EntestResult> for EntestResultRepr {
+        fn from(er: EntestResult) -> Self {
+            Self {
+                mode: er.mode,
+                range: er.range,
+                samples: er.samples,
+                mc_tries: er.mc_tries,
+                mc_in_count: er.mc_in_count,
+                mc_geometry: er.mc_geometry,
+                shannon: er.shannon,
+                compression: er.compression(),
+                min_entropy: er.min_entropy(),
+                mcv_upper_bound: er.mcv_upper_bound(),
+                renyi_hartley: er.histogram().renyi(renyi::HARTLEY),
+                renyi_collision: er.histogram().renyi(renyi::COLLISION),
+                renyi_min: er.histogram().renyi(renyi::MIN),
+                chi: er.chi,
+                chi_prob: er.chi_prob,
+                chi_prob_percent: er.chi_prob_percent(),
+                mean: er.mean,
+                mean_z_score: er.mean_z_score(),
+                mean_p_value: er.mean_p_value(),
+                mc: er.mc,
+                mc_error: er.mc_error(),
+                mc_z_score: er.mc_z_score(),
+                mc_p_value: er.mc_p_value(),
+                sc: er.sc,
+                sc_z_score: er.sc_z_score(),
+                sc_p_value: er.sc_p_value(),
+                counts: er.histogram().counts().to_vec(),
+            }
+        }
+    }
+
+    impl TryFrom<EntestResultRepr> for EntestResult {
+        type Error = &'static str;
+
+        fn try_from(repr: EntestResultRepr) -> Result<Self, Self::Error> {
+            if repr.counts.len() > repr.mode.bins() {
+                return Err("too many occurrence counts for the sampling mode");
+            }
+            if ! MonteCarloGeometry::is_valid(repr.mc_geometry.coordinate_bits(), repr.mc_geometry.dimensions()) {
+                return Err("invalid monte-carlo geometry");
+            }
+
+            let mut buckets = [0u64; 256];
+            buckets[..repr.counts.len()].copy_from_slice(&repr.counts);
+
+            Ok(Self {
+                mode: repr.mode,
+                range: repr.range,
+                buckets,
+                samples: repr.samples,
+                mc_tries: repr.mc_tries,
+                mc_in_count: repr.mc_in_count,
+                mc_geometry: repr.mc_geometry,
+                chi: repr.chi,
+                chi_prob: repr.chi_prob,
+                mc: repr.mc,
+                mean: repr.mean,
+                sc: repr.sc,
+                shannon: repr.shannon,
+            })
+        }
+    }
 }
 
 /// Entest can be used for testing random data between all test methods.
@@ -155,6 +475,12 @@ pub struct Entest {
 
     /// state of shannon calculation.
     pub shannon: ShannonCalculation,
+
+    /// the position of the first byte of input.
+    offset: u64,
+
+    /// the number of bytes updated.
+    bytes: u64,
 }
 
 impl Default for Entest {
@@ -172,6 +498,8 @@ impl Entest {
             mean: MeanCalculation::INIT,
             sc: SerialCorrelationCoefficientCalculation::INIT,
             shannon: ShannonCalculation::INIT,
+            offset: 0,
+            bytes: 0,
         };
 
     /// create new blanket state for [Entest].
@@ -181,9 +509,55 @@ impl Entest {
         Self::INIT
     }
 
+    /// create new blanket state for [Entest] with the provided [Mode].
+    ///
+    /// use `Mode::Bits(..)` for treating input as a stream of bits (like `ent -b`).
+    pub const fn with_mode(mode: Mode) -> Self {
+        Self {
+            chi: ChiSquareCalculation::with_mode(mode),
+            mc: MonteCarloCalculation::with_mode(mode),
+            mean: MeanCalculation::with_mode(mode),
+            sc: SerialCorrelationCoefficientCalculation::with_mode(mode),
+            shannon: ShannonCalculation::with_mode(mode),
+            offset: 0,
+            bytes: 0,
+        }
+    }
+
+    /// get the sampling mode of current state.
+    pub const fn mode(&self) -> Mode {
+        self.chi.mode()
+    }
+
+    /// get the occurrence counts of current state.
+    pub const fn histogram(&self) -> Histogram<'_> {
+        self.chi.histogram()
+    }
+
+    /// set the position of the first byte of input, that is recorded by [EntestResult::range].
+    ///
+    /// this does not skip any bytes, it only describes where the input bytes come from.
+    pub const fn set_offset(&mut self, offset: u64) -> &mut Self {
+        self.offset = offset;
+        self
+    }
+
+    /// set the geometry of monte-carlo points, that should be used on blanket state.
+    pub const fn set_mc_geometry(&mut self, geometry: MonteCarloGeometry) -> &mut Self {
+        self.mc.geometry = geometry;
+        self
+    }
+
+    /// get the range of bytes updated so far.
+    pub const fn range(&self) -> ByteRange {
+        ByteRange::new(self.offset, Some(self.bytes))
+    }
+
     /// update all test state inside the Entest.
     pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
-        self.chi.update(bytes);
+        self.bytes += bytes.len() as u64;
+        // the counts are shared by chi-square, mean and shannon, see [Entest::finalize].
+        self.chi.total_buckets += self.chi.counts.tally(bytes);
         //self.mean.update(bytes);
         //self.shannon.update(bytes);
 
@@ -195,20 +569,28 @@ impl Entest {
 
     /// get results from all test methods.
     pub const fn finalize(&mut self) -> EntestResult {
-        unwrap!(copy_from_slice(&mut self.mean.buckets, &self.chi.buckets));
+        self.mean.counts = self.chi.counts;
         self.mean.total_buckets = self.chi.total_buckets;
 
-        unwrap!(copy_from_slice(&mut self.shannon.buckets, &self.chi.buckets));
+        self.shannon.counts = self.chi.counts;
         self.shannon.total_buckets = self.chi.total_buckets;
 
-        let (chi, chi_prob) = self.chi.finalize_probability();
+        let total = self.chi.total_buckets;
+        let chi = self.chi.counts.chi(total);
+        let chi_prob = chisqr::chi_square_survival(chi, self.chi.counts.bins() - 1);
         EntestResult {
+            mode: self.chi.mode(),
+            range: self.range(),
+            buckets: self.chi.counts.buckets,
             samples: self.chi.samples(),
+            mc_tries: self.mc.tries(),
+            mc_in_count: self.mc.in_count(),
+            mc_geometry: self.mc.geometry(),
             chi, chi_prob,
             mc: self.mc.finalize(),
-            mean: self.mean.finalize(),
+            mean: self.mean.counts.mean(total),
             sc: self.sc.finalize(),
-            shannon: self.shannon.finalize(),
+            shannon: self.shannon.counts.shannon(total),
         }
     }
 
@@ -218,6 +600,81 @@ impl Entest {
         this.update(bytes);
         this.finalize()
     }
+
+    /// this is equivalent to `Self::with_mode(mode).update(data).finalize()`.
+    pub const fn test_mode(mode: Mode, bytes: &[u8]) -> EntestResult {
+        let mut this = Self::with_mode(mode);
+        this.update(bytes);
+        this.finalize()
+    }
+}
+
+#[cfg(feature="std")]
+impl Entest {
+    /// read all bytes from `reader` until EOF, and update all test state.
+    ///
+    /// use [TransformReader](crate::transform::TransformReader) for applying a [Transform] (such as case-folding) to input.
+    pub fn update_reader<R: std::io::Read>(&mut self, reader: &mut R) -> std::io::Result<&mut Self> {
+        let mut buf = [0u8; 8192];
+        loop {
+            let len =
+                match reader.read(&mut buf) {
+                    Ok(len) => len,
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {
+                        continue;
+                    },
+                    Err(err) => {
+                        return Err(err);
+                    },
+                };
+            if len == 0 {
+                // EOF
+                break;
+            }
+
+            self.update(&buf[..len]);
+        }
+
+        Ok(self)
+    }
+
+    /// this is equivalent to `Self::with_mode(mode).update_reader(reader)?.finalize()`.
+    pub fn from_reader<R: std::io::Read>(mode: Mode, reader: &mut R) -> std::io::Result<EntestResult> {
+        Ok(Self::with_mode(mode).update_reader(reader)?.finalize())
+    }
+
+    /// test all bytes of the file `path`.
+    pub fn from_file<P: AsRef<std::path::Path>>(mode: Mode, path: P) -> std::io::Result<EntestResult> {
+        let file = std::fs::File::open(path)?;
+        let mut file = std::io::BufReader::new(file);
+        Self::from_reader(mode, &mut file)
+    }
+
+    /// test only `range` of bytes read from `reader`, by discarding bytes before the range.
+    ///
+    /// this works for any reader, use [Entest::from_seekable_range] if `reader` is seekable.
+    /// `range.offset` is relative to the current position of `reader`.
+    pub fn from_reader_range<R: std::io::Read>(mode: Mode, reader: &mut R, range: ByteRange) -> std::io::Result<EntestResult> {
+        range.skip(reader)?;
+        Ok(Self::with_mode(mode).set_offset(range.offset).update_reader(&mut range.limit(reader))?.finalize())
+    }
+
+    /// test only `range` of bytes read from `reader`, by seeking to the start of the range.
+    ///
+    /// if seeking fails (such as for a pipe), bytes before the range is discarded instead.
+    /// `range.offset` is relative to the current position of `reader`.
+    pub fn from_seekable_range<R: std::io::Read + std::io::Seek>(mode: Mode, reader: &mut R, range: ByteRange) -> std::io::Result<EntestResult> {
+        range.seek(reader)?;
+        Ok(Self::with_mode(mode).set_offset(range.offset).update_reader(&mut range.limit(reader))?.finalize())
+    }
+
+    /// test only `range` of bytes of the file `path`.
+    pub fn from_file_range<P: AsRef<std::path::Path>>(mode: Mode, path: P, range: ByteRange) -> std::io::Result<EntestResult> {
+        let mut file = std::fs::File::open(path)?;
+        range.seek(&mut file)?;
+        let file = std::io::BufReader::new(file);
+        Ok(Self::with_mode(mode).set_offset(range.offset).update_reader(&mut range.limit(file))?.finalize())
+    }
 }
 
 #[cfg(feature="test-rng")]
@@ -0,0 +1,223 @@
+//! output formats of [EntestResult].
+
+use super::*;
+
+/// format a number like `printf("%.*f")` of C, so that output can be parsed by the same tools as `ent`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CFloat(pub(crate) Dec, pub(crate) usize);
+
+impl core::fmt::Display for CFloat {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Self(val, precision) = *self;
+        CFloat64(to_f64(val), precision).fmt(f)
+    }
+}
+
+/// same as [CFloat], but for [f64].
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CFloat64(pub(crate) f64, pub(crate) usize);
+
+impl core::fmt::Display for CFloat64 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Self(val, precision) = *self;
+        if val.is_nan() {
+            f.write_str("nan")
+        } else if val.is_infinite() {
+            f.write_str(if val > 0.0 { "inf" } else { "-inf" })
+        } else {
+            write!(f, "{val:.precision$}")
+        }
+    }
+}
+
+/// format a CSV field, quoted only if it is needed.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CsvField<'a>(pub(crate) &'a str);
+
+impl core::fmt::Display for CsvField<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let field = self.0;
+        if ! field.contains([',', '"', '\n', '\r']) {
+            return f.write_str(field);
+        }
+
+        f.write_str("\"")?;
+        for (i, part) in field.split('"').enumerate() {
+            if i > 0 {
+                f.write_str("\"\"")?;
+            }
+            f.write_str(part)?;
+        }
+        f.write_str("\"")
+    }
+}
+
+/// Terse is the CSV output of `ent -t`.
+///
+/// created by [EntestResult::terse].
+#[derive(Debug, Copy, Clone)]
+pub struct Terse<'a> {
+    result: &'a EntestResult,
+    counts: bool,
+    header: bool,
+    name: Option<&'a str>,
+}
+
+impl<'a> Terse<'a> {
+    /// creates terse output of `result`.
+    ///
+    /// if `counts` is true, rows of occurrence counts is also included (this is `ent -t -c`).
+    #[inline(always)]
+    pub const fn new(result: &'a EntestResult, counts: bool) -> Self {
+        Self { result, counts, header: true, name: None }
+    }
+
+    /// whether header rows (`0,...` and `2,...`) is included. defaults to true.
+    ///
+    /// this is useful for writing results of many files into one CSV.
+    #[inline(always)]
+    pub const fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// append a `File-name` column to every row.
+    ///
+    /// the output is no longer the same as `ent`, but extra columns is placed after all columns of `ent`.
+    #[inline(always)]
+    pub const fn with_name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+}
+
+impl core::fmt::Display for Terse<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let er = self.result;
+
+        macro_rules! end_row {
+            ($header:expr) => {
+                match self.name {
+                    Some(_) if $header => f.write_str(",File-name\n")?,
+                    Some(name) => writeln!(f, ",{}", CsvField(name))?,
+                    None => f.write_str("\n")?,
+                }
+            }
+        }
+
+        if self.header {
+            write!(f, "0,File-{}s,Entropy,Chi-square,Mean,Monte-Carlo-Pi,Serial-Correlation", er.mode().unit())?;
+            end_row!(true);
+        }
+        write!(
+            f, "1,{},{},{},{},{},{}",
+            er.samples(),
+            CFloat(*er.shannon(), 6),
+            CFloat(*er.chi(), 6),
+            CFloat(*er.mean(), 6),
+            CFloat(*er.mc(), 6),
+            CFloat(*er.sc(), 6),
+        )?;
+        end_row!(false);
+
+        if self.counts {
+            let hist = er.histogram();
+            if self.header {
+                f.write_str("2,Value,Occurrences,Fraction")?;
+                end_row!(true);
+            }
+            for (value, count) in hist.iter() {
+                write!(f, "3,{value},{count},{}", CFloat(hist.fraction(value), 6))?;
+                end_row!(false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// EntCompat is the human readable output of `ent`, with the same wording and precision.
+///
+/// created by [EntestResult::ent].
+///
+/// numbers are printed from the nearest [f64] of every result, the same as `printf` of `ent`.
+/// the [Display](core::fmt::Display) impl prints printable characters of the occurrence table as UTF-8,
+/// use `EntCompat::write_to` (requires `std` feature) for the raw ISO 8859-1 bytes that `ent` prints.
+#[derive(Debug, Copy, Clone)]
+pub struct EntCompat<'a> {
+    result: &'a EntestResult,
+    counts: bool,
+}
+
+impl<'a> EntCompat<'a> {
+    /// creates `ent` compatible output of `result`.
+    ///
+    /// if `counts` is true, the occurrence table is printed before results (this is `ent -c`).
+    #[inline(always)]
+    pub const fn new(result: &'a EntestResult, counts: bool) -> Self {
+        Self { result, counts }
+    }
+
+    /// write the output into `w`, printable characters of the occurrence table is written as ISO 8859-1 bytes.
+    ///
+    /// this is byte-for-byte the same as `ent`.
+    #[cfg(feature="std")]
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        if self.counts {
+            self.result.histogram().write_latin1(w)?;
+        }
+        write!(w, "{}", Self::new(self.result, false))
+    }
+
+    /// write everything except the occurrence table.
+    fn fmt_results(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let er = self.result;
+        let mode = er.mode();
+        let samp = mode.unit();
+        let samples = er.samples();
+        let sample_bits = mode.sample_bits() as f64;
+
+        let shannon = to_f64(*er.shannon());
+        // ent uses its own approximation of chi-square probability, which may differ in the last digit.
+        let chi_prob = to_f64(chisqr::ent_probability_chi_sq(*er.chi(), (mode.bins() - 1) as u16));
+        let mc = to_f64(*er.mc());
+        let sc = to_f64(*er.sc());
+
+        // same as `(short)` of C, that truncates toward zero.
+        let compression = (100.0 * (sample_bits - shannon) / sample_bits) as i16;
+        let mc_error = 100.0 * ((core::f64::consts::PI - mc).abs() / core::f64::consts::PI);
+        let expected_mean = CFloat(mode.expected_mean(), 1);
+
+        writeln!(f, "Entropy = {} bits per {samp}.", CFloat(*er.shannon(), 6))?;
+        f.write_str("\nOptimum compression would reduce the size\n")?;
+        write!(f, "of this {samples} {samp} file by {compression} percent.\n\n")?;
+
+        writeln!(f, "Chi square distribution for {samples} samples is {}, and randomly", CFloat(*er.chi(), 2))?;
+        if chi_prob < 0.0001 {
+            f.write_str("would exceed this value less than 0.01 percent of the times.\n\n")?;
+        } else if chi_prob > 0.9999 {
+            f.write_str("would exceed this value more than 99.99 percent of the times.\n\n")?;
+        } else {
+            write!(f, "would exceed this value {} percent of the times.\n\n", CFloat64(chi_prob * 100.0, 2))?;
+        }
+
+        writeln!(f, "Arithmetic mean value of data {samp}s is {} ({expected_mean} = random).", CFloat(*er.mean(), 4))?;
+        writeln!(f, "Monte Carlo value for Pi is {} (error {} percent).", CFloat(*er.mc(), 9), CFloat64(mc_error, 2))?;
+
+        f.write_str("Serial correlation coefficient is ")?;
+        if sc.is_nan() {
+            f.write_str("undefined (all values equal!).\n")
+        } else {
+            writeln!(f, "{} (totally uncorrelated = 0.0).", CFloat(*er.sc(), 6))
+        }
+    }
+}
+
+impl core::fmt::Display for EntCompat<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.counts {
+            write!(f, "{}", self.result.histogram())?;
+        }
+        self.fmt_results(f)
+    }
+}
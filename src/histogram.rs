@@ -0,0 +1,215 @@
+//! read-only view of occurrence counts.
+
+use super::*;
+
+/// Histogram is a read-only view of occurrence counts of every possible sample value.
+///
+/// it can be obtained from [ChiSquareCalculation::histogram], [MeanCalculation::histogram],
+/// [ShannonCalculation::histogram], [Entest::histogram] or [EntestResult::histogram].
+///
+/// the [Display](core::fmt::Display) impl prints the occurrence table of `ent -c`.
+#[derive(Debug, Copy, Clone)]
+pub struct Histogram<'a> {
+    counts: &'a [u64],
+    total: u64,
+    mode: Mode,
+}
+
+impl<'a> Histogram<'a> {
+    /// create a histogram view of provided buckets.
+    ///
+    /// only the first `mode.bins()` buckets are used.
+    #[inline(always)]
+    pub const fn new(buckets: &'a [u64; 256], total: u64, mode: Mode) -> Self {
+        Self {
+            counts: buckets.split_at(mode.bins()).0,
+            total,
+            mode,
+        }
+    }
+
+    /// the sampling mode of counted samples.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the number of possible sample values (`256` for bytes, `2` for bits).
+    #[inline(always)]
+    pub const fn bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// total count of samples.
+    #[inline(always)]
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// occurrence counts of every possible sample value, indexed by sample value.
+    #[inline(always)]
+    pub const fn counts(&self) -> &'a [u64] {
+        self.counts
+    }
+
+    /// occurrence count of `value`.
+    ///
+    /// returns zero if `value` is out of range.
+    #[inline(always)]
+    pub const fn count(&self, value: usize) -> u64 {
+        if value < self.counts.len() {
+            self.counts[value]
+        } else {
+            0
+        }
+    }
+
+    /// fraction of `value` in all samples (this is `count / total`).
+    ///
+    /// returns NaN if there is no samples.
+    #[inline(always)]
+    pub const fn fraction(&self, value: usize) -> Dec {
+        if self.total == 0 {
+            return Dec::NAN;
+        }
+        Dec::from_u64(self.count(value)).div(Dec::from_u64(self.total))
+    }
+
+    /// iterate over `(value, count)` of every possible sample value.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, u64)> + 'a {
+        self.counts.iter().copied().enumerate()
+    }
+
+    /// iterate over `(value, fraction)` of every possible sample value.
+    pub fn fractions(&self) -> impl Iterator<Item = (usize, Dec)> + 'a {
+        let this = *self;
+        (0..this.bins()).map(move |value| { (value, this.fraction(value)) })
+    }
+
+    /// iterate over values that never occurred.
+    pub fn missing(&self) -> impl Iterator<Item = usize> + 'a {
+        self.iter().filter(|(_, count)| { *count == 0 }).map(|(value, _)| { value })
+    }
+
+    /// the number of values that never occurred.
+    #[inline(always)]
+    pub const fn missing_count(&self) -> usize {
+        let mut n = 0;
+        let mut i = 0;
+        while i < self.counts.len() {
+            if self.counts[i] == 0 {
+                n += 1;
+            }
+            i += 1;
+        }
+        n
+    }
+
+    /// the most common value and its count.
+    ///
+    /// if there are ties, the smallest value is returned.
+    /// returns None if there is no samples.
+    #[inline(always)]
+    pub const fn most_common(&self) -> Option<(usize, u64)> {
+        if self.total == 0 {
+            return None;
+        }
+
+        let mut best = 0;
+        let mut i = 1;
+        while i < self.counts.len() {
+            if self.counts[i] > self.counts[best] {
+                best = i;
+            }
+            i += 1;
+        }
+        Some((best, self.counts[best]))
+    }
+
+    /// the least common value that occurred at least once, and its count.
+    ///
+    /// values that never occurred are listed by [Histogram::missing] instead.
+    /// if there are ties, the smallest value is returned.
+    /// returns None if there is no samples.
+    #[inline(always)]
+    pub const fn least_common(&self) -> Option<(usize, u64)> {
+        let mut best: Option<(usize, u64)> = None;
+        let mut i = 0;
+        while i < self.counts.len() {
+            let count = self.counts[i];
+            if count > 0 {
+                best =
+                    match best {
+                        Some((_, c)) if c <= count => best,
+                        _ => Some((i, count)),
+                    };
+            }
+            i += 1;
+        }
+        best
+    }
+
+    /// Rényi entropy of order `alpha` in bits per sample, see [renyi::renyi_entropy].
+    #[inline(always)]
+    pub const fn renyi(&self, alpha: Dec) -> Dec {
+        renyi::renyi_entropy(self.counts, self.total, alpha)
+    }
+
+    /// Rényi entropy of orders [renyi::HARTLEY], [renyi::COLLISION] and [renyi::MIN], in bits per sample.
+    #[inline(always)]
+    pub const fn renyi_orders(&self) -> (Dec, Dec, Dec) {
+        (self.renyi(renyi::HARTLEY), self.renyi(renyi::COLLISION), self.renyi(renyi::MIN))
+    }
+}
+
+/// `ent` prints characters that is printable in ISO 8859-1.
+#[inline(always)]
+const fn is_iso_print(value: usize) -> bool {
+    (value >= 32 && value < 127) || (value >= 160 && value < 256)
+}
+
+impl Histogram<'_> {
+    /// the character printed for `value` in the occurrence table, as ISO 8859-1.
+    #[inline(always)]
+    const fn table_char(&self, value: usize) -> u8 {
+        if self.mode.is_bits() || ! is_iso_print(value) {
+            b' '
+        } else {
+            value as u8
+        }
+    }
+
+    /// write the occurrence table of `ent -c` into `w`, printable characters is written as ISO 8859-1 bytes.
+    ///
+    /// this is byte-for-byte the same as `ent`,
+    /// while the [Display](core::fmt::Display) impl writes these characters as UTF-8.
+    #[cfg(feature="std")]
+    pub fn write_latin1<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(b"Value Char Occurrences Fraction\n")?;
+        for (value, count) in self.iter() {
+            if count == 0 {
+                continue;
+            }
+
+            write!(w, "{value:3}   ")?;
+            w.write_all(&[self.table_char(value)])?;
+            writeln!(w, "   {count:10}   {}", format::CFloat(self.fraction(value), 6))?;
+        }
+        write!(w, "\nTotal:    {:10}   1.000000\n\n", self.total)
+    }
+}
+
+impl core::fmt::Display for Histogram<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Value Char Occurrences Fraction\n")?;
+        for (value, count) in self.iter() {
+            if count == 0 {
+                continue;
+            }
+
+            let ch = char::from(self.table_char(value));
+            writeln!(f, "{value:3}   {ch}   {count:10}   {}", format::CFloat(self.fraction(value), 6))?;
+        }
+        write!(f, "\nTotal:    {:10}   1.000000\n\n", self.total)
+    }
+}
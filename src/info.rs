@@ -0,0 +1,125 @@
+//! build information of entest.
+
+use super::*;
+
+use fastnum2::decimal::Context;
+
+/// BuildInfo describes how this library is built.
+///
+/// results may be slightly different between `lite` and full-precision builds,
+/// so this should be archived together with results.
+#[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// version of entest crate.
+    pub version: &'static str,
+
+    /// cargo features, and whether it is enabled.
+    pub features: &'static [(&'static str, bool)],
+
+    /// name of the active [Dec] type.
+    pub dec_type: &'static str,
+
+    /// bits of the coefficient of [Dec].
+    pub dec_bits: u32,
+
+    /// significant decimal digits that [Dec] can always hold.
+    pub dec_digits: usize,
+
+    /// the decimal context used for parsing constants.
+    pub dec_ctx: Context,
+
+    /// constants that the chi-square calculation depends on.
+    pub constants: &'static [(&'static str, Dec)],
+}
+
+impl BuildInfo {
+    /// build information of current library.
+    pub const CURRENT: Self =
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            features: &[
+                ("lite", cfg!(feature="lite")),
+                ("std", cfg!(feature="std")),
+                ("test-rng", cfg!(feature="test-rng")),
+                ("serde", cfg!(feature="serde")),
+            ],
+            dec_type: if cfg!(feature="lite") { "D64" } else { "D256" },
+            dec_bits: if cfg!(feature="lite") { 64 } else { 256 },
+            dec_digits: Dec::MAX.digits_count() - 1,
+            dec_ctx: DEC_CTX,
+            constants: &[
+                ("LOG_SQRT_PI", chisqr::LOG_SQRT_PI),
+                ("I_SQRT_PI", chisqr::I_SQRT_PI),
+                ("MAX_X", chisqr::MAX_X),
+                ("MAX_Z", chisqr::MAX_Z),
+                ("HALF_LN_2PI", chisqr::HALF_LN_2PI),
+                ("PI", Dec::PI),
+            ],
+        };
+
+    /// iterate over names of enabled cargo features.
+    pub fn enabled_features(&self) -> impl Iterator<Item = &'static str> {
+        self.features.iter().filter(|(_, enabled)| { *enabled }).map(|(name, _)| { *name })
+    }
+}
+
+impl core::fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "version: {}", self.version)?;
+
+        f.write_str("features:")?;
+        for name in self.enabled_features() {
+            write!(f, " {name}")?;
+        }
+        f.write_str("\n")?;
+
+        writeln!(f, "decimal: {} ({}-bit coefficient, {} significant digits)", self.dec_type, self.dec_bits, self.dec_digits)?;
+        writeln!(f, "rounding: {}", self.dec_ctx.rounding_mode())?;
+        writeln!(f, "traps: {}", self.dec_ctx.signal_traps())?;
+        writeln!(f, "notation: {:?}", self.dec_ctx.notation())?;
+
+        f.write_str("constants:\n")?;
+        for (name, val) in self.constants {
+            writeln!(f, "    {name} = {val}")?;
+        }
+        Ok(())
+    }
+}
+
+/// serialized as a map of `version`, `features` (enabled only), `dec_type`, `dec_bits`,
+/// `dec_digits`, `rounding`, `traps`, `notation` and `constants`.
+#[cfg(feature="serde")]
+impl serde::Serialize for BuildInfo {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        /// constants are serialized as a map of name to value.
+        struct Constants(&'static [(&'static str, Dec)]);
+        impl serde::Serialize for Constants {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(self.0.iter().map(|(name, val)| { (name, val) }))
+            }
+        }
+
+        /// enabled features are serialized as a list of names.
+        struct Features(BuildInfo);
+        impl serde::Serialize for Features {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.enabled_features())
+            }
+        }
+
+        let mut s = serializer.serialize_struct("BuildInfo", 9)?;
+        s.serialize_field("version", self.version)?;
+        s.serialize_field("features", &Features(*self))?;
+        s.serialize_field("dec_type", self.dec_type)?;
+        s.serialize_field("dec_bits", &self.dec_bits)?;
+        s.serialize_field("dec_digits", &self.dec_digits)?;
+        s.serialize_field("rounding", &format_args!("{}", self.dec_ctx.rounding_mode()))?;
+        s.serialize_field("traps", &format_args!("{}", self.dec_ctx.signal_traps()))?;
+        s.serialize_field("notation", &format_args!("{:?}", self.dec_ctx.notation()))?;
+        s.serialize_field("constants", &Constants(self.constants))?;
+        s.end()
+    }
+}
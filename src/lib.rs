@@ -23,6 +23,7 @@ use alloc::{
 /// returns Ok with copied bytes if success.
 /// or return Err if `dst.len() < src.len()`
 #[inline(always)]
+#[allow(clippy::result_unit_err)]
 pub const fn copy_from_slice<T: Copy>(dst: &mut [T], src: &[T]) -> Result<usize, ()> {
     let src_len = src.len();
     let dst_len = dst.len();
@@ -81,11 +82,35 @@ pub mod cli;
 pub mod entest;
 pub use entest::{Entest, EntestResult};
 
+pub mod mode;
+pub use mode::{BitOrder, Buckets, Counts, Mode};
+
+pub mod range;
+pub use range::ByteRange;
+
+pub mod histogram;
+pub use histogram::Histogram;
+
+pub mod transform;
+pub use transform::{FoldCase, Transform};
+
+pub mod format;
+pub use format::{EntCompat, Terse};
+
+pub mod info;
+pub use info::BuildInfo;
+
+pub mod outcome;
+pub use outcome::{TestKind, TestOutcome, TestVerdict};
+
+pub mod threshold;
+pub use threshold::Thresholds;
+
 pub mod chisqr;
 pub use chisqr::ChiSquareCalculation;
 
 pub mod mc;
-pub use mc::MonteCarloCalculation;
+pub use mc::{MonteCarloCalculation, MonteCarloGeometry};
 
 pub mod mean;
 pub use mean::MeanCalculation;
@@ -96,6 +121,32 @@ pub use sc::SerialCorrelationCoefficientCalculation;
 pub mod shannon;
 pub use shannon::ShannonCalculation;
 
+pub mod mcv;
+pub use mcv::MostCommonValueCalculation;
+
+pub mod renyi;
+
+pub mod collision;
+pub use collision::CollisionCalculation;
+
+pub mod markov;
+pub use markov::MarkovCalculation;
+
+pub mod compression;
+pub use compression::CompressionCalculation;
+
+pub mod tuple;
+pub use tuple::TupleCalculation;
+
+pub mod autocorr;
+pub use autocorr::AutocorrelationCalculation;
+
+pub mod symbol;
+pub use symbol::{SymbolCounts, SymbolResult, SymbolTable};
+
+pub mod block;
+pub use block::BlockEntropyCalculation;
+
 #[cfg(test)]
 mod tests;
 
@@ -106,6 +157,38 @@ pub const fn error_ratio(correct: Dec, actual: Dec) -> Dec {
     actual.sub(correct).abs().div(correct.abs())
 }
 
+/// natural logarithm of `x`.
+///
+/// `Dec::ln` loses precision for small arguments (such as `0.01`), so this computes `-ln(1 / x)` for `x < 1`.
+#[inline(always)]
+pub(crate) const fn ln(x: Dec) -> Dec {
+    if x.lt(&Dec::ONE) && x.gt(&Dec::ZERO) {
+        Dec::ONE.div(x).ln().neg()
+    } else {
+        x.ln()
+    }
+}
+
+/// logarithm to base 2 of `x`.
+///
+/// `Dec::log2` loses precision for small arguments too, so this computes `-log2(1 / x)` for `x < 1` like [ln].
+#[inline(always)]
+pub(crate) const fn log2(x: Dec) -> Dec {
+    if x.lt(&Dec::ONE) && x.gt(&Dec::ZERO) {
+        Dec::ONE.div(x).log2().neg()
+    } else {
+        x.log2()
+    }
+}
+
+/// convert `val` to the nearest [f64].
+///
+/// this parses the decimal representation, because `Dec::to_f64` is not accurate.
+#[inline(always)]
+pub fn to_f64(val: Dec) -> f64 {
+    val.to_string().parse().unwrap_or(f64::NAN)
+}
+
 /// Tests entropy bits of provided byte stream.
 pub trait EntropyTest {
     /// provides byte stream for testing it's entropy.
@@ -113,6 +196,13 @@ pub trait EntropyTest {
 
     /// get result of entropy test.
     fn finalize(&self) -> Dec;
+
+    /// get result of entropy test, together with the expected value and p-value (if defined).
+    ///
+    /// the default implementation reports only the statistic, with NaN as expected value and zero samples.
+    fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::NAN, 0)
+    }
 }
 
 /// extension of [EntropyTest]. but it is **not** dyn-compatible (object-safety).
@@ -0,0 +1,227 @@
+//! the Markov estimate of min-entropy for binary samples (NIST SP 800-90B section 6.3.3).
+
+use super::*;
+
+/// the length of paths (`128` bits), the min-entropy of the most likely path is divided by this.
+pub const MARKOV_PATH_BITS: u32 = 128;
+
+/// Computes the Markov estimate of min-entropy on the bit stream.
+///
+/// the bits are modeled as a first-order Markov chain, with the initial probabilities `P0`, `P1`
+/// and the transition probabilities `P00`, `P01`, `P10`, `P11` (`Pxy` is the probability of `y` after `x`).
+/// the min-entropy is of the most likely path of [MARKOV_PATH_BITS] bits, that is one of:
+/// all zeros, alternating from 0, zero then ones, one then zeros, alternating from 1, or all ones.
+///
+/// a source with a strong first-order dependency (such as biased transitions) has less than 1 bit per bit,
+/// even if the count of zeros and ones is balanced.
+#[derive(Debug, Copy, Clone)]
+pub struct MarkovCalculation {
+    /// occurrence counts of bit 0 and 1
+    counts: [u64; 2],
+    /// occurrence counts of transitions, indexed by `2 * previous + next`
+    transitions: [u64; 4],
+    /// the previous bit
+    last: u8,
+    /// total bits processed
+    total: u64,
+    /// order of bits in every byte
+    order: BitOrder,
+}
+
+impl Default for MarkovCalculation {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::INIT
+    }
+}
+
+impl MarkovCalculation {
+    /// the blanket state (initial value) of [MarkovCalculation].
+    pub const INIT: Self =
+        Self {
+            counts: [0; 2],
+            transitions: [0; 4],
+            last: 0,
+            total: 0,
+            order: BitOrder::MsbFirst,
+        };
+
+    /// create new blanket state for Markov estimate.
+    ///
+    /// this just copy from [MarkovCalculation::INIT].
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::INIT
+    }
+
+    /// creates new blanket state for Markov estimate with the provided [BitOrder].
+    #[inline(always)]
+    pub const fn with_order(order: BitOrder) -> Self {
+        let mut this = Self::INIT;
+        this.order = order;
+        this
+    }
+
+    /// get the bit order of current state.
+    #[inline(always)]
+    pub const fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// apply byte stream to Markov state.
+    #[inline(always)]
+    pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        let bytes_len = bytes.len();
+
+        let mut i = 0;
+        let mut j;
+        let mut bits;
+        while i < bytes_len {
+            bits = self.order.expand(bytes[i]);
+            j = 0;
+            while j < 8 {
+                self.push(bits[j]);
+                j += 1;
+            }
+            i += 1;
+        }
+
+        self
+    }
+
+    /// apply one bit to Markov state.
+    #[inline(always)]
+    const fn push(&mut self, bit: u8) {
+        if self.total > 0 {
+            self.transitions[(self.last * 2 + bit) as usize] += 1;
+        }
+        self.counts[bit as usize] += 1;
+        self.last = bit;
+        self.total += 1;
+    }
+
+    /// get the samples (bits) of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total
+    }
+
+    /// the initial probability of `bit`, that is its fraction in all bits.
+    ///
+    /// returns NaN if there is no bits.
+    pub const fn initial(&self, bit: u8) -> Dec {
+        if self.total == 0 {
+            return Dec::NAN;
+        }
+        Dec::from_u64(self.counts[bit as usize]).div(Dec::from_u64(self.total))
+    }
+
+    /// the transition probability of `next` after `previous`.
+    ///
+    /// it is zero if `previous` is never followed by any bit.
+    pub const fn transition(&self, previous: u8, next: u8) -> Dec {
+        let from = previous as usize * 2;
+        let count = self.transitions[from + next as usize];
+        let sum = self.transitions[from] + self.transitions[from + 1];
+        if sum == 0 {
+            return Dec::ZERO;
+        }
+        Dec::from_u64(count).div(Dec::from_u64(sum))
+    }
+
+    /// the min-entropy of the most likely path of [MARKOV_PATH_BITS] bits, that is `-log2` of its probability.
+    ///
+    /// it is computed as a sum of logs, because the probability of a path can be very small (such as `2^-128`).
+    /// returns NaN if there are less than 2 bits.
+    pub const fn path_entropy(&self) -> Dec {
+        if self.total < 2 {
+            return Dec::NAN;
+        }
+        let n = MARKOV_PATH_BITS;
+        let (p0, p1) = (self.initial(0), self.initial(1));
+        let (p00, p01, p10, p11) = (self.transition(0, 0), self.transition(0, 1), self.transition(1, 0), self.transition(1, 1));
+
+        // factors and their exponents of every path.
+        let paths = [
+            [(p0, 1), (p00, n - 1), (Dec::ONE, 0)],
+            [(p0, 1), (p01, n / 2), (p10, n / 2 - 1)],
+            [(p0, 1), (p01, 1), (p11, n - 2)],
+            [(p1, 1), (p10, 1), (p00, n - 2)],
+            [(p1, 1), (p10, n / 2), (p01, n / 2 - 1)],
+            [(p1, 1), (p11, n - 1), (Dec::ONE, 0)],
+        ];
+        let mut lowest = Dec::NAN;
+        let mut i = 0;
+        while i < paths.len() {
+            let entropy = path_entropy_of(&paths[i]);
+            if ! entropy.is_nan() && (lowest.is_nan() || entropy.lt(&lowest)) {
+                lowest = entropy;
+            }
+            i += 1;
+        }
+        lowest
+    }
+
+    /// get finalize min-entropy of current byte stream, in bits per bit (at most 1).
+    ///
+    /// returns NaN if there are less than 2 bits.
+    pub const fn finalize(&self) -> Dec {
+        let entropy = self.path_entropy();
+        if entropy.is_nan() {
+            return Dec::NAN;
+        }
+        let entropy = entropy.div(Dec::from_u32(MARKOV_PATH_BITS));
+        if entropy.gt(&Dec::ONE) { Dec::ONE } else { entropy }
+    }
+
+    /// get the outcome of current state, that is expected to be 1 for random input.
+    ///
+    /// p-value is not defined for min-entropy.
+    #[inline(always)]
+    pub const fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::ONE, self.total)
+    }
+
+    /// oneshot test function for small data.
+    ///
+    /// this is equivalent to `Self::new().update(data).finalize()`.
+    #[inline(always)]
+    pub const fn test(data: &[u8]) -> Dec {
+        let mut this = Self::INIT;
+        this.update(data);
+        this.finalize()
+    }
+}
+
+/// `-log2` of the product of `factor^exponent`, or NaN if the product is zero.
+const fn path_entropy_of(terms: &[(Dec, u32); 3]) -> Dec {
+    let mut entropy = Dec::ZERO;
+    let mut i = 0;
+    while i < terms.len() {
+        let (factor, exponent) = terms[i];
+        if exponent > 0 {
+            if ! factor.gt(&Dec::ZERO) {
+                return Dec::NAN;
+            }
+            entropy = entropy.add(mcv::min_entropy_of(factor).mul(Dec::from_u32(exponent)));
+        }
+        i += 1;
+    }
+    entropy
+}
+
+impl EntropyTest for MarkovCalculation {
+    #[inline(always)]
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes);
+    }
+
+    #[inline(always)]
+    fn finalize(&self) -> Dec {
+        Self::finalize(self)
+    }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
+}
@@ -2,17 +2,278 @@
 
 use super::*;
 
-const MONTE_LEN: usize = 6;
-const MONTE_LEN_HALF: usize = MONTE_LEN / 2;
+/// max number of coordinates of a point (`8`).
+pub const MAX_DIMENSIONS: u32 = 8;
 
-/// python: ((256 ** 3) - 1) ** 2
-const IN_CIRCLE_DISTANCE: u64 = 281_474_943_156_225;
+/// max bytes of a point: [MAX_DIMENSIONS] coordinates of 32 bits.
+const MONTE_MAX_LEN: usize = (MAX_DIMENSIONS as usize) * 4;
+
+/// the probability of a random point is inside of the circle (`pi / 4`).
+pub const IN_CIRCLE_PROBABILITY: Dec = Dec::PI.div(dec!(4));
+
+/// MonteCarloGeometry is the shape of monte-carlo points: the width and the number of coordinates.
+///
+/// every coordinate is an unsigned integer of `coordinate_bits`, and a point is inside if its distance to the origin
+/// is not greater than the max value of a coordinate. that is a quarter of circle in 2D, an eighth of sphere in 3D, and
+/// so on for n-ball.
+///
+/// the fraction of points inside of a lattice is not the volume of continuous n-ball, it differs by about `1 / r` of it
+/// for the max value `r` of a coordinate (that is -0.3% for 8-bit coordinates in 2D, +0.5% in 8D), see [lattice_probability].
+/// [MonteCarloCalculation::finalize] is not corrected for it (the same as `ent`), but [MonteCarloCalculation::corrected] and
+/// the confidence interval are corrected by [MonteCarloGeometry::lattice_correction], so they agree with the significance
+/// (z-score and p-value).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonteCarloGeometry {
+    coordinate_bits: u32,
+    dimensions: u32,
+}
+
+impl Default for MonteCarloGeometry {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::ENT
+    }
+}
+
+impl MonteCarloGeometry {
+    /// the geometry of `ent`: two coordinates of 24 bits.
+    pub const ENT: Self = Self { coordinate_bits: 24, dimensions: 2 };
+
+    /// create a geometry of `dimensions` coordinates of `coordinate_bits`.
+    ///
+    /// # Panics
+    /// if it is not valid, see [MonteCarloGeometry::is_valid].
+    #[inline(always)]
+    pub const fn new(coordinate_bits: u32, dimensions: u32) -> Self {
+        assert!(Self::is_valid(coordinate_bits, dimensions), "invalid monte-carlo geometry");
+        Self { coordinate_bits, dimensions }
+    }
+
+    /// checks whether `coordinate_bits` is one of 8, 16, 24 or 32, and `dimensions` is in `2..=MAX_DIMENSIONS`.
+    #[inline(always)]
+    pub const fn is_valid(coordinate_bits: u32, dimensions: u32) -> bool {
+        matches!(coordinate_bits, 8 | 16 | 24 | 32) && dimensions >= 2 && dimensions <= MAX_DIMENSIONS
+    }
+
+    /// the width of every coordinate, in bits.
+    #[inline(always)]
+    pub const fn coordinate_bits(&self) -> u32 {
+        self.coordinate_bits
+    }
+
+    /// the number of coordinates of a point.
+    #[inline(always)]
+    pub const fn dimensions(&self) -> u32 {
+        self.dimensions
+    }
+
+    /// the number of bytes used by a point.
+    #[inline(always)]
+    pub const fn point_len(&self) -> usize {
+        (self.coordinate_bits / 8 * self.dimensions) as usize
+    }
+
+    /// the probability of a random point is inside, see [lattice_probability].
+    #[inline(always)]
+    pub const fn probability(&self) -> Dec {
+        lattice_probability(self.coordinate_bits, self.dimensions)
+    }
+
+    /// the ratio of the volume of continuous n-ball to the lattice, that is `ball_probability / lattice_probability`.
+    ///
+    /// it is close to 1 (`1 + 4.3e-8` for [MonteCarloGeometry::ENT]).
+    #[inline(always)]
+    pub const fn lattice_correction(&self) -> Dec {
+        ball_probability(self.dimensions).div(self.probability())
+    }
+
+    /// the pi estimate from `fraction` of points inside, see [pi_from_fraction].
+    ///
+    /// the fraction is multiplied by [MonteCarloGeometry::lattice_correction] first, so a perfect source gives pi.
+    #[inline(always)]
+    pub const fn pi_from_fraction(&self, fraction: Dec) -> Dec {
+        if fraction.is_nan() {
+            return Dec::NAN;
+        }
+        pi_from_fraction(fraction.mul(self.lattice_correction()), self.dimensions)
+    }
+}
+
+/// formatted as `BITSxDIMENSIONS`, such as `24x2`.
+impl core::fmt::Display for MonteCarloGeometry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}x{}", self.coordinate_bits, self.dimensions)
+    }
+}
+
+/// the rational part of the volume of unit n-ball: `V(n) = c(n) * pi^floor(n / 2)`.
+///
+/// python: `c(0) = 1; c(1) = 2; c(n) = c(n - 2) * 2 / n`
+const fn ball_coefficient(dimensions: u32) -> Dec {
+    let mut c = if dimensions.is_multiple_of(2) { chisqr::ONE } else { chisqr::TWO };
+    let mut d = 2 + (dimensions % 2);
+    while d <= dimensions {
+        c = c.mul(chisqr::TWO).div(Dec::from_u32(d));
+        d += 2;
+    }
+    c
+}
+
+/// the probability of a random point is inside of n-ball, that is the volume of unit n-ball divided by `2^n`.
+///
+/// this is `pi / 4` for 2 dimensions, `pi / 6` for 3 dimensions.
+pub const fn ball_probability(dimensions: u32) -> Dec {
+    let mut v = ball_coefficient(dimensions);
+    let mut i = 0;
+    while i < dimensions / 2 {
+        v = v.mul(Dec::PI);
+        i += 1;
+    }
+    v.div(Dec::from_u64(1 << dimensions))
+}
+
+/// the probability of a random point of `coordinate_bits` integer coordinates is inside of n-ball.
+///
+/// coordinates are integers in `0..=r` (`r = 2^coordinate_bits - 1`), so this is not exactly [ball_probability]:
+/// points on the axes are counted fully, and there are `(r + 1)^n` points, that makes it differ by about `1 / r` of it.
+/// this adds the volume of those faces (lower dimensional balls), that is exact up to about `1 / r^2`:
+///
+/// `sum(binomial(n, k) * V(n - k) * r^(n - k) for k in 0..=n) / (2 * (r + 1))^n`
+pub const fn lattice_probability(coordinate_bits: u32, dimensions: u32) -> Dec {
+    let points = Dec::from_u64(1 << coordinate_bits);
+    let ratio = points.sub(chisqr::ONE).div(points);
+
+    let mut sum = Dec::ZERO;
+    let mut binomial = chisqr::ONE;
+    let mut k = 0;
+    while k <= dimensions {
+        let n = dimensions - k;
+        let mut term = ball_probability(n).mul(Dec::from_u64(1 << n)).mul(binomial);
+        let mut i = 0;
+        while i < n {
+            term = term.mul(ratio);
+            i += 1;
+        }
+        i = 0;
+        while i < k {
+            term = term.div(points);
+            i += 1;
+        }
+        sum = sum.add(term);
+
+        binomial = binomial.mul(Dec::from_u32(n)).div(Dec::from_u32(k + 1));
+        k += 1;
+    }
+    sum.div(Dec::from_u64(1 << dimensions))
+}
+
+/// the pi estimate from `fraction` of points inside of n-ball, by solving [ball_probability] for pi.
+///
+/// this is `4 * fraction` for 2 dimensions, `6 * fraction` for 3 dimensions.
+pub const fn pi_from_fraction(fraction: Dec, dimensions: u32) -> Dec {
+    if fraction.is_nan() {
+        return Dec::NAN;
+    }
+    let power = Dec::from_u64(1 << dimensions).mul(fraction).div(ball_coefficient(dimensions));
+    let k = dimensions / 2;
+    if k == 1 || power.eq(&Dec::ZERO) {
+        power
+    } else {
+        ln(power).div(Dec::from_u32(k)).exp()
+    }
+}
+
+/// standard score of `in_count` of `tries` points, since `in_count` is binomial with `p = probability`.
+///
+/// returns NaN if `tries` is zero.
+pub const fn ball_z_score(in_count: u64, tries: u64, probability: Dec) -> Dec {
+    if tries == 0 {
+        return Dec::NAN;
+    }
+    let p = probability;
+    let n = Dec::from_u64(tries);
+    let expected = n.mul(p);
+    let sd = expected.mul(chisqr::ONE.sub(p)).sqrt();
+    Dec::from_u64(in_count).sub(expected).div(sd)
+}
+
+/// standard score of `in_count` of `tries` points, since `in_count` is binomial with `p = pi / 4`.
+///
+/// returns NaN if `tries` is zero.
+#[inline(always)]
+pub const fn pi_z_score(in_count: u64, tries: u64) -> Dec {
+    ball_z_score(in_count, tries, IN_CIRCLE_PROBABILITY)
+}
+
+/// two-sided p-value of [pi_z_score].
+#[inline(always)]
+pub const fn pi_p_value(in_count: u64, tries: u64) -> Dec {
+    chisqr::normal_p_two_sided(pi_z_score(in_count, tries))
+}
+
+/// the wilson score interval of the fraction `in_count / tries`.
+///
+/// returns NaN for both bounds if `tries` is zero.
+const fn wilson_interval(in_count: u64, tries: u64, z: Dec) -> (Dec, Dec) {
+    if tries == 0 {
+        return (Dec::NAN, Dec::NAN);
+    }
+    let n = Dec::from_u64(tries);
+    let p = Dec::from_u64(in_count).div(n);
+    let z2 = z.mul(z);
+    let denom = chisqr::ONE.add(z2.div(n));
+    let center = p.add(z2.div(n.mul(chisqr::TWO))).div(denom);
+    let half =
+        p.mul(chisqr::ONE.sub(p)).div(n)
+        .add(z2.div(n.mul(n).mul(dec!(4))))
+        .sqrt()
+        .mul(z)
+        .div(denom);
+    let low = center.sub(half);
+    let low = if low.lt(&Dec::ZERO) { Dec::ZERO } else { low };
+    (low, center.add(half))
+}
+
+/// confidence interval of the pi estimate `4 * in_count / tries`, by the wilson score interval.
+///
+/// `z` is the z-score of the confidence level, such as [chisqr::NORMAL_Z_95].
+/// this is for continuous coordinates, see [ball_confidence_interval] for the lattice of a [MonteCarloGeometry].
+/// returns NaN for both bounds if `tries` is zero.
+#[inline(always)]
+pub const fn pi_confidence_interval(in_count: u64, tries: u64, z: Dec) -> (Dec, Dec) {
+    let (low, high) = wilson_interval(in_count, tries, z);
+    (pi_from_fraction(low, 2), pi_from_fraction(high, 2))
+}
+
+/// confidence interval of the pi estimate from points of `geometry` inside of n-ball.
+///
+/// both bounds are computed by [MonteCarloGeometry::pi_from_fraction], so the interval is around the corrected
+/// estimate ([MonteCarloCalculation::corrected]) like [ball_z_score] with [MonteCarloGeometry::probability].
+/// returns NaN for both bounds if `tries` is zero.
+pub const fn ball_confidence_interval(in_count: u64, tries: u64, z: Dec, geometry: MonteCarloGeometry) -> (Dec, Dec) {
+    let (low, high) = wilson_interval(in_count, tries, z);
+    (geometry.pi_from_fraction(low), geometry.pi_from_fraction(high))
+}
+
+/// the pi estimate of `in_count` of `tries` points with the lattice correction, see [MonteCarloGeometry::pi_from_fraction].
+///
+/// returns NaN if `tries` is zero.
+pub(crate) const fn corrected_pi(in_count: u64, tries: u64, geometry: MonteCarloGeometry) -> Dec {
+    if tries == 0 {
+        return Dec::NAN;
+    }
+    geometry.pi_from_fraction(Dec::from_u64(in_count).div(Dec::from_u64(tries)))
+}
 
 /// See <https://www.geeksforgeeks.org/estimating-value-pi-using-monte-carlo>
 #[derive(Debug, Copy, Clone)]
 pub struct MonteCarloCalculation {
     /// Bytes used by Monte Carlo coordinates
-    monte: [u8; MONTE_LEN],
+    monte: [u8; MONTE_MAX_LEN],
+
+    /// Geometry of points
+    pub(crate) geometry: MonteCarloGeometry,
 
     /// Accumulator pointer
     accumulator: usize,
@@ -22,6 +283,9 @@ pub struct MonteCarloCalculation {
 
     /// Inside Count
     in_count: u64,
+
+    /// Sampling mode
+    mode: Mode,
 }
 
 impl Default for MonteCarloCalculation {
@@ -35,10 +299,12 @@ impl MonteCarloCalculation {
     /// the blanket state (initial value) of [MonteCarloCalculation].
     pub const INIT: Self =
         Self {
-            monte: [0; MONTE_LEN],
+            monte: [0; MONTE_MAX_LEN],
+            geometry: MonteCarloGeometry::ENT,
             accumulator: 0,
             tries: 0,
             in_count: 0,
+            mode: Mode::Bytes,
         };
 
     /// create new blanket state for monte-carlo calculation.
@@ -49,36 +315,84 @@ impl MonteCarloCalculation {
         Self::INIT
     }
 
+    /// creates new blanket state for monte-carlo calculation with the provided [Mode].
+    ///
+    /// in bit mode, coordinates are built from the bit stream (48 bits per try with [MonteCarloGeometry::ENT]),
+    /// so [BitOrder::MsbFirst] gives the same result as byte mode, just like `ent -b`.
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        let mut this = Self::INIT;
+        this.mode = mode;
+        this
+    }
+
+    /// set the geometry of points, that should be used on blanket state.
+    ///
+    /// the default is [MonteCarloGeometry::ENT].
+    #[inline(always)]
+    pub const fn with_geometry(mut self, geometry: MonteCarloGeometry) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// get the geometry of points of current state.
+    #[inline(always)]
+    pub const fn geometry(&self) -> MonteCarloGeometry {
+        self.geometry
+    }
+
     /// apply byte stream to monte-carlo state.
     #[inline(always)]
     pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
         let bytes_len = bytes.len();
+        let order =
+            match self.mode {
+                Mode::Bits(order) => order,
+                Mode::Bytes => BitOrder::MsbFirst,
+            };
 
-        let mut x: u64;
-        let mut y: u64;
+        let point_len = self.geometry.point_len();
+        let coordinate_len = (self.geometry.coordinate_bits / 8) as usize;
+        // python: ((2 ** bits) - 1) ** 2
+        let radius = (1u128 << self.geometry.coordinate_bits) - 1;
+        let in_distance = radius * radius;
+
+        let mut x: u128;
+        let mut distance: u128;
 
         let mut i = 0;
         let mut j;
         while i < bytes_len {
-            self.monte[self.accumulator] = bytes[i];
+            self.monte[self.accumulator] = order.msb_first(bytes[i]);
             i += 1;
 
             self.accumulator += 1;
-            if self.accumulator >= MONTE_LEN {
+            if self.accumulator >= point_len {
                 self.accumulator = 0;
                 self.tries += 1;
 
                 x = 0;
-                y = 0;
+                distance = 0;
                 j = 0;
-                while j < MONTE_LEN_HALF {
-                    x = (x * 256) + (self.monte[j] as u64);
-                    y = (y * 256) + (self.monte[j + 3] as u64);
+                while j < point_len {
+                    x = (x * 256) + (self.monte[j] as u128);
 
                     j += 1;
+                    if j % coordinate_len == 0 {
+                        distance += x * x;
+                        x = 0;
+                    }
                 }
 
-                if (x * x) + (y * y) < IN_CIRCLE_DISTANCE {
+                // points on the circle (such as `(r, 0)`) are inside, that is `<=` in `randtest.c` of ent.
+                // lattice_probability counts them in the same way, so the z-score has no bias of the boundary.
+                if distance <= in_distance {
                     self.in_count += 1;
                 }
             }
@@ -88,6 +402,8 @@ impl MonteCarloCalculation {
     }
 
     /// get finalize monte-carlo result of current byte stream.
+    ///
+    /// this is not corrected for the lattice of coordinates, that is the value of `ent` (`4 * in_count / tries` in 2D).
     #[inline(always)]
     pub const fn finalize(&self) -> Dec {
         if self.tries == 0 {
@@ -95,7 +411,63 @@ impl MonteCarloCalculation {
         }
         let in_count = Dec::from_u64(self.in_count);
         let tries = Dec::from_u64(self.tries);
-        dec!(4.0).mul(in_count.div(tries))
+        pi_from_fraction(in_count.div(tries), self.geometry.dimensions)
+    }
+
+    /// the pi estimate corrected for the lattice of coordinates, see [MonteCarloGeometry::pi_from_fraction].
+    ///
+    /// a perfect source gives pi, and [MonteCarloCalculation::confidence_interval] is around it.
+    #[inline(always)]
+    pub const fn corrected(&self) -> Dec {
+        corrected_pi(self.in_count, self.tries, self.geometry)
+    }
+
+    /// error of the monte-carlo value, in percent of pi.
+    #[inline(always)]
+    pub const fn error(&self) -> Dec {
+        let mc = self.finalize();
+        if mc.is_nan() {
+            return Dec::NAN;
+        }
+        error_ratio(Dec::PI, mc).mul(dec!(100.0))
+    }
+
+    /// get the outcome of current state, that is expected to be pi for random input.
+    ///
+    /// samples of the outcome is the number of points (tries).
+    #[inline(always)]
+    pub const fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::PI, self.tries).with_p_value(self.p_value())
+    }
+
+    /// get the number of points (tries) of current state.
+    #[inline(always)]
+    pub const fn tries(&self) -> u64 {
+        self.tries
+    }
+
+    /// get the number of points inside of the circle (or n-ball).
+    #[inline(always)]
+    pub const fn in_count(&self) -> u64 {
+        self.in_count
+    }
+
+    /// standard score of the points inside, see [ball_z_score].
+    #[inline(always)]
+    pub const fn z_score(&self) -> Dec {
+        ball_z_score(self.in_count, self.tries, self.geometry.probability())
+    }
+
+    /// two-sided p-value of [MonteCarloCalculation::z_score].
+    #[inline(always)]
+    pub const fn p_value(&self) -> Dec {
+        chisqr::normal_p_two_sided(self.z_score())
+    }
+
+    /// confidence interval of the pi estimate, see [ball_confidence_interval].
+    #[inline(always)]
+    pub const fn confidence_interval(&self, z: Dec) -> (Dec, Dec) {
+        ball_confidence_interval(self.in_count, self.tries, z, self.geometry)
     }
 
     /// oneshot test function for small data.
@@ -109,6 +481,25 @@ impl MonteCarloCalculation {
     }
 }
 
+/// the value, error, and significance of current state.
+impl core::fmt::Display for MonteCarloCalculation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (low, high) = self.confidence_interval(chisqr::NORMAL_Z_95);
+        writeln!(
+            f,
+            "Monte Carlo value for Pi with {}-bit coordinates in {} dimensions is {} (error {} percent).",
+            self.geometry.coordinate_bits, self.geometry.dimensions,
+            format::CFloat(self.finalize(), 9), format::CFloat(self.error(), 2),
+        )?;
+        writeln!(
+            f,
+            "The error is {} standard deviations (p-value {}), 95% confidence interval is {} ..= {}.",
+            format::CFloat(self.z_score(), 2), format::CFloat(self.p_value(), 4),
+            format::CFloat(low, 6), format::CFloat(high, 6),
+        )
+    }
+}
+
 impl EntropyTest for MonteCarloCalculation {
     #[inline(always)]
     fn update(&mut self, bytes: &[u8]) {
@@ -119,5 +510,9 @@ impl EntropyTest for MonteCarloCalculation {
     fn finalize(&self) -> Dec {
         Self::finalize(self)
     }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
 }
 
@@ -0,0 +1,209 @@
+//! the most common value estimate of min-entropy (NIST SP 800-90B section 6.3.1).
+
+use super::*;
+
+/// z-score of the confidence bounds in SP 800-90B (`2.576`), that is [chisqr::NORMAL_Z_99] rounded as the standard does.
+pub const Z_ALPHA: Dec = dec!(2.576);
+
+/// min-entropy of the probability `p` of the most likely sample, that is `-log2(p)` in bits.
+///
+/// returns NaN if `p` is NaN or not positive.
+#[inline(always)]
+pub const fn min_entropy_of(p: Dec) -> Dec {
+    if p.is_nan() || ! p.gt(&Dec::ZERO) {
+        return Dec::NAN;
+    }
+    // that is `log2(1 / p)`, which is not negative for `p <= 1`.
+    log2(Dec::ONE.div(p))
+}
+
+/// the upper bound of the probability `p` that is estimated from `total` samples.
+///
+/// `p_u = min(1, p + Z_ALPHA * sqrt(p * (1 - p) / (total - 1)))`.
+/// returns NaN if `p` is NaN or `total` is zero, and 1 if `total` is one.
+pub const fn upper_bound(p: Dec, total: u64) -> Dec {
+    if total == 0 || p.is_nan() {
+        return Dec::NAN;
+    }
+    if total == 1 {
+        return Dec::ONE;
+    }
+    let p_u = p.add(Z_ALPHA.mul(p.mul(Dec::ONE.sub(p)).div(Dec::from_u64(total - 1)).sqrt()));
+    if p_u.gt(&Dec::ONE) { Dec::ONE } else { p_u }
+}
+
+/// the upper bound of the probability of the most common value, that is counted `max_count` times of `total` samples.
+///
+/// this is [upper_bound] of `max_count / total`.
+/// returns NaN if `total` is zero, and 1 if `total` is one.
+pub const fn mcv_upper_bound(max_count: u64, total: u64) -> Dec {
+    if total == 0 {
+        return Dec::NAN;
+    }
+    upper_bound(Dec::from_u64(max_count).div(Dec::from_u64(total)), total)
+}
+
+/// the most common value estimate of min-entropy in bits per sample, that is `-log2(p_u)` of [mcv_upper_bound].
+#[inline(always)]
+pub const fn mcv_min_entropy(max_count: u64, total: u64) -> Dec {
+    min_entropy_of(mcv_upper_bound(max_count, total))
+}
+
+/// the largest count of `buckets`.
+#[inline(always)]
+pub(crate) const fn max_count(buckets: &[u64]) -> u64 {
+    let mut max = 0;
+    let mut i = 0;
+    while i < buckets.len() {
+        if buckets[i] > max {
+            max = buckets[i];
+        }
+        i += 1;
+    }
+    max
+}
+
+/// Computes the most common value estimate of min-entropy.
+///
+/// for random input, it is a bit less than [Mode::sample_bits], because the upper bound of the probability is used
+/// (a conservative estimate). unlike shannon entropy, it only depends on the most common value.
+///
+/// it counts byte or bit samples in [Buckets] by default, or symbols of other widths in [SymbolCounts].
+#[derive(Debug, Copy, Clone)]
+pub struct MostCommonValueCalculation<C = Buckets> {
+    pub(crate) counts: C,
+    pub(crate) total_buckets: u64,
+}
+
+impl Default for MostCommonValueCalculation {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::INIT
+    }
+}
+
+impl<C: Counts> MostCommonValueCalculation<C> {
+    /// creates new blanket state for most common value estimate of the provided empty counts.
+    #[inline(always)]
+    pub const fn with_counts(counts: C) -> Self {
+        Self {
+            counts,
+            total_buckets: 0,
+        }
+    }
+
+    /// get the occurrence counts of current state.
+    #[inline(always)]
+    pub const fn counts(&self) -> &C {
+        &self.counts
+    }
+
+    /// get the samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total_buckets
+    }
+
+    /// apply byte stream to most common value state.
+    #[inline(always)]
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.total_buckets += self.counts.tally(bytes);
+        self
+    }
+
+    /// the upper bound of the probability of the most common value, see [mcv_upper_bound].
+    #[inline(always)]
+    pub fn upper_bound(&self) -> Dec {
+        mcv_upper_bound(self.counts.max_count(), self.total_buckets)
+    }
+
+    /// get finalize min-entropy of current byte stream, in bits per sample.
+    #[inline(always)]
+    pub fn finalize(&self) -> Dec {
+        min_entropy_of(self.upper_bound())
+    }
+
+    /// get the outcome of current state, that is expected to be [Counts::sample_bits] for random input.
+    ///
+    /// p-value is not defined for min-entropy.
+    #[inline(always)]
+    pub fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::from_u32(self.counts.sample_bits()), self.total_buckets)
+    }
+}
+
+/// reuse the counts of chi-square calculation, like [Entest::finalize] does for mean and shannon.
+impl<C> From<ChiSquareCalculation<C>> for MostCommonValueCalculation<C> {
+    #[inline(always)]
+    fn from(chi: ChiSquareCalculation<C>) -> Self {
+        Self {
+            counts: chi.counts,
+            total_buckets: chi.total_buckets,
+        }
+    }
+}
+
+impl MostCommonValueCalculation {
+    /// the blanket state (initial value) of [MostCommonValueCalculation].
+    pub const INIT: Self = Self::with_counts(Buckets::INIT);
+
+    /// create new blanket state for most common value estimate.
+    ///
+    /// this just copy from [MostCommonValueCalculation::INIT].
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::INIT
+    }
+
+    /// creates new blanket state for most common value estimate with the provided [Mode].
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        Self::with_counts(Buckets::with_mode(mode))
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.counts.mode()
+    }
+
+    /// get the occurrence counts of current state.
+    #[inline(always)]
+    pub const fn histogram(&self) -> Histogram<'_> {
+        self.counts.histogram(self.total_buckets)
+    }
+
+    /// oneshot test function for small data.
+    ///
+    /// this is equivalent to `Self::new().update(data).finalize()`.
+    #[inline(always)]
+    pub fn test(data: &[u8]) -> Dec {
+        let mut this = Self::INIT;
+        this.update(data);
+        this.finalize()
+    }
+}
+
+impl MostCommonValueCalculation<SymbolCounts> {
+    /// creates new blanket state for most common value estimate of `bits` wide symbols, see [SymbolCounts::new].
+    #[inline(always)]
+    pub fn with_symbols(bits: u32, order: BitOrder) -> Self {
+        Self::with_counts(SymbolCounts::new(bits).with_order(order))
+    }
+}
+
+impl<C: Counts> EntropyTest for MostCommonValueCalculation<C> {
+    #[inline(always)]
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes);
+    }
+
+    #[inline(always)]
+    fn finalize(&self) -> Dec {
+        Self::finalize(self)
+    }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
+}
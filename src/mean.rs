@@ -2,10 +2,71 @@
 
 use super::*;
 
+/// the variance of a sample uniformly distributed over `bins` values (`0..bins`), that is `(bins^2 - 1) / 12`.
+///
+/// (`5461.25` for bytes, `0.25` for bits)
+#[inline(always)]
+pub const fn uniform_variance(bins: usize) -> Dec {
+    uniform_variance_of(Dec::from_usize(bins))
+}
+
+/// same as [uniform_variance], for `bins` that may not fit in `usize` (such as wide symbols).
+#[inline(always)]
+pub const fn uniform_variance_of(bins: Dec) -> Dec {
+    bins.mul(bins).sub(Dec::ONE).div(dec!(12))
+}
+
+/// standard score of `mean` of `samples` samples, compared with the mean of uniform samples over `bins` values.
+///
+/// that is `(mean - (bins - 1) / 2) / sqrt(uniform_variance(bins) / samples)`.
+/// returns NaN if `mean` is NaN or `samples` is zero.
+#[inline(always)]
+pub const fn mean_z_score(mean: Dec, samples: u64, bins: usize) -> Dec {
+    mean_z_score_of(mean, samples, Dec::from_usize(bins))
+}
+
+/// same as [mean_z_score], for `bins` that may not fit in `usize` (such as wide symbols).
+#[inline(always)]
+pub const fn mean_z_score_of(mean: Dec, samples: u64, bins: Dec) -> Dec {
+    if mean.is_nan() || samples == 0 {
+        return Dec::NAN;
+    }
+    let expected = bins.sub(Dec::ONE).div(dec!(2));
+    let se = uniform_variance_of(bins).div(Dec::from_u64(samples)).sqrt();
+    mean.sub(expected).div(se)
+}
+
+/// arithmetic mean of the first `bins` buckets, that counts `total` samples (bucket `i` is value `i`).
+///
+/// returns NaN if `total` is zero.
+#[inline(always)]
+pub const fn mean_of_bins(buckets: &[u64], bins: usize, total: u64) -> Dec {
+    if total == 0 {
+        return Dec::NAN;
+    }
+
+    let mut sum = dec!(0.0);
+
+    let mut i = 0;
+    let mut index;
+    let mut bucket;
+    while i < bins {
+        index = Dec::from_usize(i);
+        bucket = Dec::from_u64(buckets[i]);
+        sum = sum.add(index.mul(bucket));
+
+        i += 1;
+    }
+
+    sum.div(Dec::from_u64(total))
+}
+
 /// Computes the Mean Entropy test
+///
+/// it counts byte or bit samples in [Buckets] by default, or symbols of other widths in [SymbolCounts].
 #[derive(Debug, Clone, Copy)]
-pub struct MeanCalculation {
-    pub(crate) buckets: [u64; 256],
+pub struct MeanCalculation<C = Buckets> {
+    pub(crate) counts: C,
     pub(crate) total_buckets: u64,
 }
 
@@ -16,75 +77,120 @@ impl Default for MeanCalculation {
     }
 }
 
-impl MeanCalculation {
-    /// the blanket state (initial value) of [MeanCalculation].
-    pub const INIT: Self =
+impl<C: Counts> MeanCalculation<C> {
+    /// creates new blanket state for mean calculation of the provided empty counts.
+    #[inline(always)]
+    pub const fn with_counts(counts: C) -> Self {
         Self {
-            buckets: [0; 256],
+            counts,
             total_buckets: 0,
-        };
+        }
+    }
 
-    /// create new blanket state for mean calculation.
-    ///
-    /// this just copy from [MeanCalculation::INIT].
-    pub const fn new() -> Self {
-        Self::INIT
+    /// get the occurrence counts of current state.
+    #[inline(always)]
+    pub const fn counts(&self) -> &C {
+        &self.counts
     }
 
-    /// apply byte stream to mean state.
-    pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
-        let mut i = 0;
-        let bytes_len = bytes.len();
-        while i < bytes_len {
-            self.buckets[bytes[i] as usize] += 1;
-            i += 1;
-        }
-        self.total_buckets += bytes_len as u64;
+    /// get the samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total_buckets
+    }
 
+    /// apply byte stream to mean state.
+    #[inline(always)]
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.total_buckets += self.counts.tally(bytes);
         self
     }
 
     /// get finalize mean result of current byte stream.
     #[inline(always)]
-    pub const fn finalize(&self) -> Dec {
-        if self.total_buckets == 0 {
-            return Dec::NAN;
-        }
+    pub fn finalize(&self) -> Dec {
+        self.counts.mean(self.total_buckets)
+    }
 
-        let mut sum = dec!(0.0);
+    /// get the outcome of current state, that is expected to be [Counts::expected_mean] for random input.
+    #[inline(always)]
+    pub fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), self.counts.expected_mean(), self.total_buckets).with_p_value(self.p_value())
+    }
+
+    /// standard score of the mean, see [mean_z_score_of].
+    #[inline(always)]
+    pub fn z_score(&self) -> Dec {
+        mean_z_score_of(self.finalize(), self.total_buckets, Dec::from_u64(self.counts.bins()))
+    }
 
-        let mut i = 0;
-        let mut index;
-        let mut bucket;
-        while i < 256 {
-            index = Dec::from_usize(i);
-            bucket = Dec::from_u64(self.buckets[i]);
-            sum = sum.add(index.mul(bucket));
+    /// two-sided p-value of [MeanCalculation::z_score].
+    #[inline(always)]
+    pub fn p_value(&self) -> Dec {
+        chisqr::normal_p_two_sided(self.z_score())
+    }
+}
 
-            i += 1;
+/// reuse the counts of chi-square calculation, like [Entest::finalize] does.
+impl<C> From<ChiSquareCalculation<C>> for MeanCalculation<C> {
+    #[inline(always)]
+    fn from(chi: ChiSquareCalculation<C>) -> Self {
+        Self {
+            counts: chi.counts,
+            total_buckets: chi.total_buckets,
         }
+    }
+}
 
-        sum.div(Dec::from_u64(self.total_buckets))
+impl MeanCalculation {
+    /// the blanket state (initial value) of [MeanCalculation].
+    pub const INIT: Self = Self::with_counts(Buckets::INIT);
+
+    /// create new blanket state for mean calculation.
+    ///
+    /// this just copy from [MeanCalculation::INIT].
+    pub const fn new() -> Self {
+        Self::INIT
     }
 
-    /// get the samples of current state.
+    /// creates new blanket state for mean calculation with the provided [Mode].
     #[inline(always)]
-    pub const fn samples(&self) -> u64 {
-        self.total_buckets
+    pub const fn with_mode(mode: Mode) -> Self {
+        Self::with_counts(Buckets::with_mode(mode))
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.counts.mode()
+    }
+
+    /// get the occurrence counts of current state.
+    #[inline(always)]
+    pub const fn histogram(&self) -> Histogram<'_> {
+        self.counts.histogram(self.total_buckets)
     }
 
     /// oneshot test function for small data.
     ///
     /// this is equivalent to `Self::new().update(data).finalize()`.
     #[inline(always)]
-    pub const fn test(data: &[u8]) -> Dec {
+    pub fn test(data: &[u8]) -> Dec {
         let mut this = Self::INIT;
         this.update(data);
         this.finalize()
     }
 }
 
-impl EntropyTest for MeanCalculation {
+impl MeanCalculation<SymbolCounts> {
+    /// creates new blanket state for mean calculation of `bits` wide symbols, see [SymbolCounts::new].
+    #[inline(always)]
+    pub fn with_symbols(bits: u32, order: BitOrder) -> Self {
+        Self::with_counts(SymbolCounts::new(bits).with_order(order))
+    }
+}
+
+impl<C: Counts> EntropyTest for MeanCalculation<C> {
     #[inline(always)]
     fn update(&mut self, bytes: &[u8]) {
         Self::update(self, bytes);
@@ -94,5 +200,8 @@ impl EntropyTest for MeanCalculation {
     fn finalize(&self) -> Dec {
         Self::finalize(self)
     }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
 }
-
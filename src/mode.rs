@@ -0,0 +1,296 @@
+//! sampling mode of input: bytes or a stream of bits.
+
+use super::*;
+
+/// the order of bits taken from each byte, when input is treated as a stream of bits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum BitOrder {
+    /// most significant bit first (this is the behavior of `ent -b`).
+    #[default]
+    MsbFirst,
+
+    /// least significant bit first.
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// reorder bits of `byte`, so that the first bit of the stream is the most significant bit.
+    #[inline(always)]
+    pub const fn msb_first(self, byte: u8) -> u8 {
+        match self {
+            Self::MsbFirst => byte,
+            Self::LsbFirst => byte.reverse_bits(),
+        }
+    }
+
+    /// expand `byte` into 8 samples (each one is `0` or `1`) in this bit order.
+    #[inline(always)]
+    pub const fn expand(self, byte: u8) -> [u8; 8] {
+        let byte = self.msb_first(byte);
+        let mut out = [0u8; 8];
+        let mut i = 0;
+        while i < 8 {
+            out[i] = (byte >> (7 - i)) & 1;
+            i += 1;
+        }
+        out
+    }
+}
+
+/// how the input stream is split into samples.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum Mode {
+    /// every byte is a sample (the default of `ent`).
+    #[default]
+    Bytes,
+
+    /// every bit is a sample (this is `ent -b`).
+    Bits(BitOrder),
+}
+
+impl Mode {
+    /// the number of possible sample values (`256` for bytes, `2` for bits).
+    #[inline(always)]
+    pub const fn bins(self) -> usize {
+        match self {
+            Self::Bytes => 256,
+            Self::Bits(_) => 2,
+        }
+    }
+
+    /// the number of bits in one sample.
+    #[inline(always)]
+    pub const fn sample_bits(self) -> u32 {
+        match self {
+            Self::Bytes => 8,
+            Self::Bits(_) => 1,
+        }
+    }
+
+    /// the expected arithmetic mean of random samples (`127.5` for bytes, `0.5` for bits).
+    #[inline(always)]
+    pub const fn expected_mean(self) -> Dec {
+        match self {
+            Self::Bytes => dec!(127.5),
+            Self::Bits(_) => dec!(0.5),
+        }
+    }
+
+    /// the name of one sample (`"byte"` or `"bit"`).
+    #[inline(always)]
+    pub const fn unit(self) -> &'static str {
+        match self {
+            Self::Bytes => "byte",
+            Self::Bits(_) => "bit",
+        }
+    }
+
+    /// checks whether input is treated as a stream of bits.
+    #[inline(always)]
+    pub const fn is_bits(self) -> bool {
+        matches!(self, Self::Bits(_))
+    }
+}
+
+/// count every sample of `bytes` into `buckets`, then returns the number of samples.
+#[inline(always)]
+const fn tally(buckets: &mut [u64; 256], mode: Mode, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    let bytes_len = bytes.len();
+    match mode {
+        Mode::Bytes => {
+            while i < bytes_len {
+                buckets[bytes[i] as usize] += 1;
+                i += 1;
+            }
+            bytes_len as u64
+        },
+        Mode::Bits(_) => {
+            // bit order does not matter for counting.
+            let mut ones = 0;
+            while i < bytes_len {
+                ones += bytes[i].count_ones() as u64;
+                i += 1;
+            }
+            let total = (bytes_len as u64) * 8;
+            buckets[1] += ones;
+            buckets[0] += total - ones;
+            total
+        },
+    }
+}
+
+/// Buckets is occurrence counts of byte or bit samples, that is counted by [ChiSquareCalculation], [MeanCalculation] and [ShannonCalculation].
+///
+/// the symbols of other widths are counted by [SymbolCounts].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Buckets {
+    pub(crate) buckets: [u64; 256],
+    pub(crate) mode: Mode,
+}
+
+impl Default for Buckets {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::INIT
+    }
+}
+
+impl Buckets {
+    /// the blanket state (initial value) of [Buckets].
+    pub const INIT: Self =
+        Self {
+            buckets: [0; 256],
+            mode: Mode::Bytes,
+        };
+
+    /// create empty counts of samples of the provided [Mode].
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        let mut this = Self::INIT;
+        this.mode = mode;
+        this
+    }
+
+    /// get the sampling mode of counts.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// the number of possible sample values (`256` for bytes, `2` for bits).
+    #[inline(always)]
+    pub const fn bins(&self) -> u64 {
+        self.mode.bins() as u64
+    }
+
+    /// the number of bits in one sample.
+    #[inline(always)]
+    pub const fn sample_bits(&self) -> u32 {
+        self.mode.sample_bits()
+    }
+
+    /// the expected arithmetic mean of random samples.
+    #[inline(always)]
+    pub const fn expected_mean(&self) -> Dec {
+        self.mode.expected_mean()
+    }
+
+    /// count every sample of `bytes`, then returns the number of samples.
+    #[inline(always)]
+    pub const fn tally(&mut self, bytes: &[u8]) -> u64 {
+        tally(&mut self.buckets, self.mode, bytes)
+    }
+
+    /// chi-square statistic of these counts of `total` samples, see [chisqr::chi_statistic_bins].
+    #[inline(always)]
+    pub const fn chi(&self, total: u64) -> Dec {
+        chisqr::chi_statistic_bins(&self.buckets, self.mode.bins(), total)
+    }
+
+    /// arithmetic mean of these counts of `total` samples, see [mean::mean_of_bins].
+    #[inline(always)]
+    pub const fn mean(&self, total: u64) -> Dec {
+        mean::mean_of_bins(&self.buckets, self.mode.bins(), total)
+    }
+
+    /// shannon entropy of these counts of `total` samples, see [shannon::shannon_of_bins].
+    #[inline(always)]
+    pub const fn shannon(&self, total: u64) -> Dec {
+        shannon::shannon_of_bins(&self.buckets, self.mode.bins(), total)
+    }
+
+    /// get the occurrence counts of `total` samples.
+    #[inline(always)]
+    pub const fn histogram(&self, total: u64) -> Histogram<'_> {
+        Histogram::new(&self.buckets, total, self.mode)
+    }
+}
+
+/// Counts is occurrence counts of samples, that is counted by [ChiSquareCalculation], [MeanCalculation] and [ShannonCalculation].
+///
+/// it is implemented by [Buckets] (bytes or bits) and [SymbolCounts] (symbols of any width).
+pub trait Counts {
+    /// count every sample of `bytes`, then returns the number of samples.
+    fn tally(&mut self, bytes: &[u8]) -> u64;
+
+    /// the number of possible sample values.
+    fn bins(&self) -> u64;
+
+    /// the number of bits in one sample.
+    fn sample_bits(&self) -> u32;
+
+    /// occurrence count of the sample value `index`, that is zero if `index` is not less than [Counts::bins].
+    fn count(&self, index: u64) -> u64;
+
+    /// the largest occurrence count, that is the count of the most common value.
+    fn max_count(&self) -> u64;
+
+    /// the expected arithmetic mean of random samples, that is `(bins - 1) / 2`.
+    #[inline(always)]
+    fn expected_mean(&self) -> Dec {
+        Dec::from_u64(self.bins() - 1).div(chisqr::TWO)
+    }
+
+    /// chi-square statistic of these counts of `total` samples.
+    fn chi(&self, total: u64) -> Dec;
+
+    /// arithmetic mean of these counts of `total` samples.
+    fn mean(&self, total: u64) -> Dec;
+
+    /// shannon entropy of these counts of `total` samples, in bits per sample.
+    fn shannon(&self, total: u64) -> Dec;
+}
+
+impl Counts for Buckets {
+    #[inline(always)]
+    fn tally(&mut self, bytes: &[u8]) -> u64 {
+        Self::tally(self, bytes)
+    }
+
+    #[inline(always)]
+    fn bins(&self) -> u64 {
+        Self::bins(self)
+    }
+
+    #[inline(always)]
+    fn sample_bits(&self) -> u32 {
+        Self::sample_bits(self)
+    }
+
+    #[inline(always)]
+    fn count(&self, index: u64) -> u64 {
+        if index < self.bins() {
+            self.buckets[index as usize]
+        } else {
+            0
+        }
+    }
+
+    #[inline(always)]
+    fn max_count(&self) -> u64 {
+        mcv::max_count(&self.buckets)
+    }
+
+    #[inline(always)]
+    fn expected_mean(&self) -> Dec {
+        Self::expected_mean(self)
+    }
+
+    #[inline(always)]
+    fn chi(&self, total: u64) -> Dec {
+        Self::chi(self, total)
+    }
+
+    #[inline(always)]
+    fn mean(&self, total: u64) -> Dec {
+        Self::mean(self, total)
+    }
+
+    #[inline(always)]
+    fn shannon(&self, total: u64) -> Dec {
+        Self::shannon(self, total)
+    }
+}
@@ -0,0 +1,153 @@
+//! common outcome of every test: statistic, expected value, p-value and verdict.
+
+use super::*;
+
+/// how many times of the significance level is considered as weak (`10`).
+pub const WEAK_FACTOR: Dec = dec!(10);
+
+/// the kind of a test.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum TestKind {
+    /// shannon entropy.
+    Entropy,
+
+    /// chi-square of occurrence counts.
+    ChiSquare,
+
+    /// arithmetic mean.
+    Mean,
+
+    /// monte-carlo value for pi.
+    MonteCarlo,
+
+    /// serial correlation coefficient.
+    SerialCorrelation,
+}
+
+impl TestKind {
+    /// all kinds of test, in the order of `ent` output.
+    pub const ALL: [Self; 5] = [Self::Entropy, Self::ChiSquare, Self::Mean, Self::MonteCarlo, Self::SerialCorrelation];
+
+    /// short name of this test.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Entropy => "entropy",
+            Self::ChiSquare => "chi-square",
+            Self::Mean => "mean",
+            Self::MonteCarlo => "monte-carlo",
+            Self::SerialCorrelation => "serial correlation",
+        }
+    }
+}
+
+/// the verdict of a test at some significance level.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum TestVerdict {
+    /// p-value is not less than [WEAK_FACTOR] times of the significance level.
+    Pass,
+
+    /// p-value is less than [WEAK_FACTOR] times of the significance level, but not less than it.
+    Weak,
+
+    /// p-value is less than the significance level (or it is NaN).
+    Fail,
+}
+
+impl TestVerdict {
+    /// short name of this verdict (`"pass"`, `"weak"` or `"fail"`).
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Weak => "weak",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+impl core::fmt::Display for TestVerdict {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// TestOutcome is the common result of every test.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct TestOutcome {
+    /// the statistic computed from input (same as the result of `finalize`).
+    pub statistic: Dec,
+
+    /// the expected value of the statistic for truly random input.
+    pub expected: Dec,
+
+    /// probability of truly random input produces a statistic this extreme, if it is defined for this test.
+    pub p_value: Option<Dec>,
+
+    /// whether p-values close to 1 also indicate non-randomness (such as a chi-square fit that is too good).
+    ///
+    /// if so, [TestOutcome::verdict] uses `2 * min(p, 1 - p)` instead of `p`.
+    pub both_tails: bool,
+
+    /// the number of samples that the statistic is computed from.
+    pub samples: u64,
+}
+
+impl TestOutcome {
+    /// create an outcome without p-value.
+    #[inline(always)]
+    pub const fn new(statistic: Dec, expected: Dec, samples: u64) -> Self {
+        Self { statistic, expected, p_value: None, both_tails: false, samples }
+    }
+
+    /// set [TestOutcome::p_value].
+    #[inline(always)]
+    pub const fn with_p_value(mut self, p_value: Dec) -> Self {
+        self.p_value = Some(p_value);
+        self
+    }
+
+    /// set [TestOutcome::both_tails].
+    #[inline(always)]
+    pub const fn with_both_tails(mut self, both_tails: bool) -> Self {
+        self.both_tails = both_tails;
+        self
+    }
+
+    /// the verdict at the significance level `alpha` (such as `0.01`).
+    ///
+    /// returns None if p-value is not defined for this test.
+    /// NaN p-value (such as for empty input) is always [TestVerdict::Fail].
+    pub const fn verdict(&self, alpha: Dec) -> Option<TestVerdict> {
+        let p =
+            match self.p_value {
+                Some(p) => p,
+                None => {
+                    return None;
+                },
+            };
+        if p.is_nan() {
+            return Some(TestVerdict::Fail);
+        }
+
+        let p =
+            if self.both_tails {
+                let q = chisqr::ONE.sub(p);
+                (if p.lt(&q) { p } else { q }).mul(chisqr::TWO)
+            } else {
+                p
+            };
+
+        Some(
+            if p.lt(&alpha) {
+                TestVerdict::Fail
+            } else if p.lt(&alpha.mul(WEAK_FACTOR)) {
+                TestVerdict::Weak
+            } else {
+                TestVerdict::Pass
+            }
+        )
+    }
+}
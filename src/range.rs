@@ -0,0 +1,102 @@
+//! a range of input bytes to analyze.
+
+#[cfg(feature="std")]
+use std::io::{Read, Seek, SeekFrom};
+
+/// ByteRange is a part of input: `length` bytes starting at `offset`.
+///
+/// `length` of None means until EOF.
+/// in [EntestResult](crate::EntestResult), `length` is always Some (the number of bytes actually analyzed).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteRange {
+    /// the position of the first byte.
+    pub offset: u64,
+
+    /// the number of bytes, or None for until EOF.
+    pub length: Option<u64>,
+}
+
+impl ByteRange {
+    /// the whole input: from offset 0 until EOF.
+    pub const ALL: Self = Self { offset: 0, length: None };
+
+    /// create a range of `length` bytes starting at `offset`.
+    #[inline(always)]
+    pub const fn new(offset: u64, length: Option<u64>) -> Self {
+        Self { offset, length }
+    }
+
+    /// set [ByteRange::offset].
+    #[inline(always)]
+    pub const fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// set [ByteRange::length].
+    #[inline(always)]
+    pub const fn with_length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// the position after the last byte, or None if it is until EOF (or overflows).
+    #[inline(always)]
+    pub const fn end(&self) -> Option<u64> {
+        match self.length {
+            Some(length) => self.offset.checked_add(length),
+            None => None,
+        }
+    }
+
+    /// checks whether this range is the whole input.
+    #[inline(always)]
+    pub const fn is_all(&self) -> bool {
+        self.offset == 0 && self.length.is_none()
+    }
+}
+
+#[cfg(feature="std")]
+impl ByteRange {
+    /// move `reader` to the start of this range, by reading and discarding [ByteRange::offset] bytes.
+    ///
+    /// this works for any reader (such as standard input).
+    /// returns the number of discarded bytes, that is less than `offset` if EOF is reached.
+    pub fn skip<R: Read>(&self, reader: &mut R) -> std::io::Result<u64> {
+        std::io::copy(&mut reader.take(self.offset), &mut std::io::sink())
+    }
+
+    /// move `reader` to the start of this range, by seeking [ByteRange::offset] bytes forward.
+    ///
+    /// if the reader is not seekable (such as a pipe), this falls back to [ByteRange::skip].
+    pub fn seek<R: Read + Seek>(&self, reader: &mut R) -> std::io::Result<()> {
+        let seeked =
+            match i64::try_from(self.offset) {
+                Ok(offset) => reader.seek(SeekFrom::Current(offset)).is_ok(),
+                Err(_) => false,
+            };
+        if ! seeked {
+            self.skip(reader)?;
+        }
+        Ok(())
+    }
+
+    /// limit `reader` to [ByteRange::length] bytes.
+    ///
+    /// the reader should be moved to the start of this range before (see [ByteRange::seek] and [ByteRange::skip]).
+    pub fn limit<R: Read>(&self, reader: R) -> std::io::Take<R> {
+        reader.take(self.length.unwrap_or(u64::MAX))
+    }
+}
+
+/// formatted as `OFFSET..END`, or `OFFSET..` if it is until EOF.
+impl core::fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}..", self.offset)?;
+        if let Some(end) = self.end() {
+            write!(f, "{end}")?;
+        }
+        Ok(())
+    }
+}
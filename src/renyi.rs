@@ -0,0 +1,79 @@
+//! the Rényi entropy family.
+//!
+//! the Rényi entropy of order `α` is `log2(sum(p^α)) / (1 - α)` in bits,
+//! with the limits `α = 1` (shannon entropy) and `α = ∞` (min-entropy, `-log2(max(p))`).
+//! it is non-increasing in `α`, and all orders are equal only for a uniform distribution over the observed values,
+//! so comparing orders tells a flat but skewed distribution from a uniform one.
+//!
+//! these are plug-in estimates from occurrence counts, see [Histogram::renyi].
+
+use super::*;
+
+/// the order `α = 0` (`0`): Hartley entropy, that is `log2` of the number of observed values.
+pub const HARTLEY: Dec = Dec::ZERO;
+
+/// the order `α = 2` (`2`): collision entropy, that is `-log2` of the probability that two samples are equal.
+pub const COLLISION: Dec = chisqr::TWO;
+
+/// the order `α = ∞`: min-entropy, that is `-log2` of the probability of the most common value.
+pub const MIN: Dec = Dec::INFINITY;
+
+/// Rényi entropy of order `alpha` for occurrence `counts` of `total` samples, in bits per sample.
+///
+/// `alpha` can be any non-negative value including [Dec::INFINITY]. `alpha = 1` is the shannon entropy.
+/// returns NaN if `total` is zero, or `alpha` is negative or NaN.
+pub const fn renyi_entropy(counts: &[u64], total: u64, alpha: Dec) -> Dec {
+    if total == 0 || alpha.is_nan() || alpha.lt(&Dec::ZERO) {
+        return Dec::NAN;
+    }
+
+    let mut observed = 0;
+    let mut max = 0;
+    let mut i = 0;
+    while i < counts.len() {
+        if counts[i] > 0 {
+            observed += 1;
+        }
+        if counts[i] > max {
+            max = counts[i];
+        }
+        i += 1;
+    }
+
+    if alpha.is_infinite() {
+        return log2(Dec::from_u64(total).div(Dec::from_u64(max)));
+    }
+    if alpha.eq(&HARTLEY) {
+        return log2(Dec::from_u64(observed));
+    }
+    if alpha.eq(&Dec::ONE) {
+        let mut entropy = Dec::ZERO;
+        i = 0;
+        while i < counts.len() {
+            entropy = entropy.add(shannon::entropy_term(counts[i], total));
+            i += 1;
+        }
+        return entropy;
+    }
+
+    // sum of `(count / max)^α`, so that it is in `1..=observed` for any α.
+    // then `sum(p^α) = (max / total)^α * sum`.
+    let mut sum = Dec::ZERO;
+    let max = Dec::from_u64(max);
+    i = 0;
+    while i < counts.len() {
+        if counts[i] > 0 {
+            let ratio = Dec::from_u64(counts[i]).div(max);
+            let term =
+                if alpha.eq(&COLLISION) {
+                    ratio.mul(ratio)
+                } else {
+                    ln(ratio).mul(alpha).exp()
+                };
+            sum = sum.add(term);
+        }
+        i += 1;
+    }
+    let log_sum = alpha.mul(log2(max.div(Dec::from_u64(total)))).add(log2(sum));
+    log_sum.div(Dec::ONE.sub(alpha))
+}
@@ -2,25 +2,86 @@
 
 use super::*;
 
-/// Computes the serial correlation coefficient. In the event of input all 0s, reporst 1.0
+/// how many standard deviations is the width of [sc_band] (`2`).
+pub const SC_BAND_SIGMAS: Dec = dec!(2);
+
+/// the expected serial correlation coefficient of `samples` random samples, that is `-1 / (samples - 1)`.
+///
+/// returns NaN if `samples` is less than 2.
+#[inline(always)]
+pub const fn sc_expected(samples: u64) -> Dec {
+    if samples < 2 {
+        return Dec::NAN;
+    }
+    Dec::ONE.neg().div(Dec::from_u64(samples - 1))
+}
+
+/// the standard deviation of serial correlation coefficient of `samples` random samples.
+///
+/// that is `samples / ((samples - 1) * sqrt(samples - 2))` (Knuth, TAOCP vol. 2, 3.3.2), approx `1 / sqrt(samples)`.
+/// returns NaN if `samples` is less than 3.
+#[inline(always)]
+pub const fn sc_std_dev(samples: u64) -> Dec {
+    if samples < 3 {
+        return Dec::NAN;
+    }
+    let n = Dec::from_u64(samples);
+    n.div(Dec::from_u64(samples - 1).mul(Dec::from_u64(samples - 2).sqrt()))
+}
+
+/// the range that serial correlation coefficient of `samples` random samples is inside of about 95% of the times.
+///
+/// that is [sc_expected] ± [SC_BAND_SIGMAS] * [sc_std_dev] (approx `±2 / sqrt(samples)`).
+#[inline(always)]
+pub const fn sc_band(samples: u64) -> (Dec, Dec) {
+    let mean = sc_expected(samples);
+    let sd = sc_std_dev(samples);
+    if sd.is_nan() {
+        return (Dec::NAN, Dec::NAN);
+    }
+    let width = sd.mul(SC_BAND_SIGMAS);
+    (mean.sub(width), mean.add(width))
+}
+
+/// standard score of serial correlation coefficient `sc` of `samples` samples.
+///
+/// returns NaN if `sc` is NaN or `samples` is less than 3.
+#[inline(always)]
+pub const fn sc_z_score(sc: Dec, samples: u64) -> Dec {
+    let sd = sc_std_dev(samples);
+    if sc.is_nan() || sd.is_nan() {
+        return Dec::NAN;
+    }
+    sc.sub(sc_expected(samples)).div(sd)
+}
+
+/// Computes the serial correlation coefficient of the circular definition (Knuth, TAOCP vol. 2, 3.3.2):
+///
+/// `(n * sum(u[i] * u[(i + 1) % n]) - sum(u[i])^2) / (n * sum(u[i]^2) - sum(u[i])^2)`
+///
+/// that is, every sample is paired with the next one, and the last sample is paired with the first one (same as `ent`).
+/// the result is exact for every input length, and does not depend on how the input is split across `update` calls.
+/// if all samples are equal (or no samples), the result is NaN.
 #[derive(Debug, Clone, Copy)]
 pub struct SerialCorrelationCoefficientCalculation {
     /// Whether all values is equal
     all_equals: bool,
     /// first time
     first: bool,
-    /// term 1
+    /// `sum(u[i - 1] * u[i])` of samples so far, without the wraparound pair
     t1: u64,
-    /// term 2
+    /// `sum(u[i])`
     t2: u64,
-    /// term 3
+    /// `sum(u[i]^2)`
     t3: u64,
-    /// last byte
+    /// the previous sample
     last: u8,
-    /// first byte
+    /// the first sample, that is paired with the last one
     u0: u8,
-    /// total bytes processed
+    /// total samples processed
     total: u64,
+    /// sampling mode
+    mode: Mode,
 }
 
 impl Default for SerialCorrelationCoefficientCalculation {
@@ -42,6 +103,7 @@ impl SerialCorrelationCoefficientCalculation {
             last: 0,
             u0: 0,
             total: 0,
+            mode: Mode::Bytes,
         };
 
     /// create new blanket state for serial-correlation-coefficient calculation.
@@ -52,43 +114,74 @@ impl SerialCorrelationCoefficientCalculation {
         Self::INIT
     }
 
+    /// creates new blanket state for serial-correlation-coefficient calculation with the provided [Mode].
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        let mut this = Self::INIT;
+        this.mode = mode;
+        this
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
     /// apply byte stream to serial-correlation-coefficient state.
     #[inline(always)]
     pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
         let bytes_len = bytes.len();
-        if bytes_len == 0 {
-            return self;
-        }
 
         let mut i = 0;
-        if self.first {
-            self.first = false;
-            self.last = 0;
-            self.u0 = bytes[0];
-            i = 1;
+        let mut j;
+        let mut bits;
+        while i < bytes_len {
+            match self.mode {
+                Mode::Bytes => {
+                    self.push(bytes[i]);
+                    self.total += 1;
+                },
+                Mode::Bits(order) => {
+                    bits = order.expand(bytes[i]);
+                    j = 0;
+                    while j < 8 {
+                        self.push(bits[j]);
+                        j += 1;
+                    }
+                    self.total += 8;
+                },
+            }
+
+            i += 1;
         }
 
-        let mut un;
-        let mut b;
-        while i < bytes_len {
-            b = bytes[i];
+        self
+    }
 
+    /// apply one sample to serial-correlation-coefficient state.
+    ///
+    /// the pair of the previous sample and this one is added, except for the first sample that has no previous one.
+    /// its pair with the last sample (the wraparound) is added in [SerialCorrelationCoefficientCalculation::finalize],
+    /// so the state is the same no matter where the input is split.
+    #[inline(always)]
+    const fn push(&mut self, b: u8) {
+        let un = b as u64;
+        if self.first {
+            self.first = false;
+            self.u0 = b;
+        } else {
             if self.all_equals && self.u0 != b {
                 self.all_equals = false;
             }
 
-            un = b as u64;
             // mul is loss-less for native integer type
             self.t1 += (self.last as u64) * un;
-            self.t2 += un;
-            self.t3 += un * un;
-            self.last = b;
-
-            i += 1;
         }
-        self.total += bytes_len as u64;
 
-        self
+        self.t2 += un;
+        self.t3 += un * un;
+        self.last = b;
     }
 
     /// checks whether all bytes equals.
@@ -98,6 +191,8 @@ impl SerialCorrelationCoefficientCalculation {
     }
 
     /// get finalize serial-correlation-coefficient result of current byte stream.
+    ///
+    /// this closes the circle by the pair of the last and the first sample, then applies the definition.
     #[inline(always)]
     pub const fn finalize(&self) -> Dec {
         if self.total == 0 || self.all_equals {
@@ -105,20 +200,40 @@ impl SerialCorrelationCoefficientCalculation {
         }
 
         let total = Dec::from_u64(self.total);
-        let u0 = Dec::from_u8(self.u0);
-        let t1 = Dec::from_u8(self.last).mul(u0).add(Dec::from_u64(self.t1));
-        let t2 = Dec::from_u64(self.t2).powi(2);
-        let mut scc = total.mul(Dec::from_u64(self.t3)).sub(t2);
-
-        // Should never see scc = 0.0 for non-zero inputs and not "all-values equal".
-        // Declare this as positively correlated.
-        if scc.eq(&dec!(0.0)) {
-            //scc = dec!(1.0);
-        } else {
-            scc = total.mul(t1).sub(t2).div(scc);
+        let t1 = Dec::from_u64(self.t1 + (self.last as u64) * (self.u0 as u64));
+        let t2 = Dec::from_u64(self.t2);
+        let t2 = t2.mul(t2);
+        let denominator = total.mul(Dec::from_u64(self.t3)).sub(t2);
+
+        // the denominator is `n^2` times the variance, that is zero only if all samples are equal.
+        if denominator.eq(&Dec::ZERO) {
+            return Dec::NAN;
         }
+        total.mul(t1).sub(t2).div(denominator)
+    }
 
-        scc
+    /// get the outcome of current state, that is expected to be [sc_expected] (close to zero) for random input.
+    #[inline(always)]
+    pub const fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), sc_expected(self.total), self.total).with_p_value(self.p_value())
+    }
+
+    /// standard score of the serial correlation coefficient, see [sc_z_score].
+    #[inline(always)]
+    pub const fn z_score(&self) -> Dec {
+        sc_z_score(self.finalize(), self.total)
+    }
+
+    /// two-sided p-value of [SerialCorrelationCoefficientCalculation::z_score].
+    #[inline(always)]
+    pub const fn p_value(&self) -> Dec {
+        chisqr::normal_p_two_sided(self.z_score())
+    }
+
+    /// get the samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total
     }
 
     /// oneshot test function for small data.
@@ -142,4 +257,8 @@ impl EntropyTest for SerialCorrelationCoefficientCalculation {
     fn finalize(&self) -> Dec {
         Self::finalize(self)
     }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
 }
@@ -2,10 +2,47 @@
 
 use super::*;
 
+/// the entropy of a value that is counted `count` times of `total` samples, in bits.
+///
+/// that is `p * log2(1 / p)` for `p = count / total`, and zero if `count` is zero.
+/// the shannon entropy is the sum of this over every possible value.
+#[inline(always)]
+pub const fn entropy_term(count: u64, total: u64) -> Dec {
+    let probability = Dec::from_u64(count).div(Dec::from_u64(total));
+    if probability.gt(&Dec::ZERO) {
+        probability.mul(log2(Dec::ONE.div(probability)))
+    } else {
+        Dec::ZERO
+    }
+}
+
+/// shannon entropy of the first `bins` buckets, that counts `total` samples, in bits per sample.
+///
+/// returns NaN if `total` is zero.
+#[inline(always)]
+pub const fn shannon_of_bins(buckets: &[u64], bins: usize, total: u64) -> Dec {
+    if total == 0 {
+        return Dec::NAN;
+    }
+
+    let mut entropy = dec!(0.0);
+
+    let mut i = 0;
+    while i < bins {
+        if buckets[i] > 0 {
+            entropy = entropy.add(entropy_term(buckets[i], total));
+        }
+        i += 1;
+    }
+    entropy
+}
+
 /// Computes the Shannon Entropy test
+///
+/// it counts byte or bit samples in [Buckets] by default, or symbols of other widths in [SymbolCounts].
 #[derive(Debug, Copy, Clone)]
-pub struct ShannonCalculation {
-    pub(crate) buckets: [u64; 256],
+pub struct ShannonCalculation<C = Buckets> {
+    pub(crate) counts: C,
     pub(crate) total_buckets: u64,
 }
 
@@ -16,13 +53,64 @@ impl Default for ShannonCalculation {
     }
 }
 
-impl ShannonCalculation {
-    /// the blanket state (initial value) of [ShannonCalculation].
-    pub const INIT: Self =
+impl<C: Counts> ShannonCalculation<C> {
+    /// creates new blanket state for shannon calculation of the provided empty counts.
+    #[inline(always)]
+    pub const fn with_counts(counts: C) -> Self {
         Self {
-            buckets: [0; 256],
+            counts,
             total_buckets: 0,
-        };
+        }
+    }
+
+    /// get the occurrence counts of current state.
+    #[inline(always)]
+    pub const fn counts(&self) -> &C {
+        &self.counts
+    }
+
+    /// get the samples of current state.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.total_buckets
+    }
+
+    /// apply byte stream to shannon state.
+    #[inline(always)]
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.total_buckets += self.counts.tally(bytes);
+        self
+    }
+
+    /// get finalize shannon result of current byte stream, in bits per sample.
+    #[inline(always)]
+    pub fn finalize(&self) -> Dec {
+        self.counts.shannon(self.total_buckets)
+    }
+
+    /// get the outcome of current state, that is expected to be [Counts::sample_bits] for random input.
+    ///
+    /// p-value is not defined for shannon entropy.
+    #[inline(always)]
+    pub fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::from_u32(self.counts.sample_bits()), self.total_buckets)
+    }
+}
+
+/// reuse the counts of chi-square calculation, like [Entest::finalize] does.
+impl<C> From<ChiSquareCalculation<C>> for ShannonCalculation<C> {
+    #[inline(always)]
+    fn from(chi: ChiSquareCalculation<C>) -> Self {
+        Self {
+            counts: chi.counts,
+            total_buckets: chi.total_buckets,
+        }
+    }
+}
+
+impl ShannonCalculation {
+    /// the blanket state (initial value) of [ShannonCalculation].
+    pub const INIT: Self = Self::with_counts(Buckets::INIT);
 
     /// create new blanket state for shannon calculation.
     ///
@@ -32,62 +120,44 @@ impl ShannonCalculation {
         Self::INIT
     }
 
-    /// apply byte stream to shannon state.
+    /// creates new blanket state for shannon calculation with the provided [Mode].
     #[inline(always)]
-    pub const fn update(&mut self, bytes: &[u8]) -> &mut Self {
-        let mut i = 0;
-        let bytes_len = bytes.len();
-        while i < bytes_len {
-            self.buckets[bytes[i] as usize] += 1;
-            i += 1;
-        }
-        self.total_buckets += bytes_len as u64;
-
-        self
+    pub const fn with_mode(mode: Mode) -> Self {
+        Self::with_counts(Buckets::with_mode(mode))
     }
 
-    /// get finalize shannon result of current byte stream.
+    /// get the sampling mode of current state.
     #[inline(always)]
-    pub const fn finalize(&self) -> Dec {
-        if self.total_buckets == 0 {
-            return Dec::NAN;
-        }
-
-        let length = Dec::from_u64(self.total_buckets);
-        let mut entropy = dec!(0.0);
-
-        let mut i = 0;
-        let mut probability;
-        let mut p;
-        while i < 256 {
-            probability = Dec::from_u64(self.buckets[i]).div(length);
-            if probability.gt(&Dec::ZERO) {
-                p = Dec::ONE.div(probability).log2();
-                entropy = entropy.add(probability.mul(p));
-            }
-            i += 1;
-        }
-        entropy
+    pub const fn mode(&self) -> Mode {
+        self.counts.mode()
     }
 
-    /// get the samples of current state.
+    /// get the occurrence counts of current state.
     #[inline(always)]
-    pub const fn samples(&self) -> u64 {
-        self.total_buckets
+    pub const fn histogram(&self) -> Histogram<'_> {
+        self.counts.histogram(self.total_buckets)
     }
 
     /// oneshot test function for small data.
     ///
     /// this is equivalent to `Self::new().update(data).finalize()`.
     #[inline(always)]
-    pub const fn test(data: &[u8]) -> Dec {
+    pub fn test(data: &[u8]) -> Dec {
         let mut this = Self::INIT;
         this.update(data);
         this.finalize()
     }
 }
 
-impl EntropyTest for ShannonCalculation {
+impl ShannonCalculation<SymbolCounts> {
+    /// creates new blanket state for shannon calculation of `bits` wide symbols, see [SymbolCounts::new].
+    #[inline(always)]
+    pub fn with_symbols(bits: u32, order: BitOrder) -> Self {
+        Self::with_counts(SymbolCounts::new(bits).with_order(order))
+    }
+}
+
+impl<C: Counts> EntropyTest for ShannonCalculation<C> {
     #[inline(always)]
     fn update(&mut self, bytes: &[u8]) {
         Self::update(self, bytes);
@@ -97,4 +167,8 @@ impl EntropyTest for ShannonCalculation {
     fn finalize(&self) -> Dec {
         Self::finalize(self)
     }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
 }
@@ -0,0 +1,392 @@
+//! symbols of configurable width: occurrence counts of symbols, for chi-square, mean and shannon entropy.
+
+use super::*;
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+/// max width of a symbol (`32`).
+pub const MAX_SYMBOL_BITS: u32 = 32;
+
+/// max width of a symbol that is counted in a dense table (`16`), wider symbols are counted in a sparse map.
+pub const MAX_DENSE_BITS: u32 = 16;
+
+/// SymbolTable is occurrence counts of symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolTable {
+    /// a count for every possible symbol, indexed by symbol value.
+    Dense(Vec<u64>),
+
+    /// counts of observed symbols only.
+    Sparse(BTreeMap<u64, u64>),
+}
+
+impl SymbolTable {
+    /// create empty counts of `bits` wide symbols.
+    ///
+    /// it is [SymbolTable::Dense] if `bits` is not greater than [MAX_DENSE_BITS], otherwise [SymbolTable::Sparse].
+    pub fn new(bits: u32) -> Self {
+        if bits <= MAX_DENSE_BITS {
+            Self::Dense(vec![0; 1 << bits])
+        } else {
+            Self::Sparse(BTreeMap::new())
+        }
+    }
+
+    /// count one `symbol`.
+    #[inline(always)]
+    pub fn add(&mut self, symbol: u64) {
+        match self {
+            Self::Dense(counts) => {
+                counts[symbol as usize] += 1;
+            },
+            Self::Sparse(counts) => {
+                *counts.entry(symbol).or_insert(0) += 1;
+            },
+        }
+    }
+
+    /// occurrence count of `symbol`.
+    pub fn count(&self, symbol: u64) -> u64 {
+        match self {
+            Self::Dense(counts) => usize::try_from(symbol).ok().and_then(|i| { counts.get(i) }).copied().unwrap_or(0),
+            Self::Sparse(counts) => counts.get(&symbol).copied().unwrap_or(0),
+        }
+    }
+
+    /// iterate over observed symbols and their counts, ordered by symbol value.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let (dense, sparse) =
+            match self {
+                Self::Dense(counts) => (Some(counts), None),
+                Self::Sparse(counts) => (None, Some(counts)),
+            };
+        let dense = dense.into_iter().flat_map(|counts| { counts.iter().enumerate() }).map(|(symbol, &count)| { (symbol as u64, count) });
+        let sparse = sparse.into_iter().flat_map(|counts| { counts.iter() }).map(|(&symbol, &count)| { (symbol, count) });
+        dense.chain(sparse).filter(|(_, count)| { *count > 0 })
+    }
+
+    /// shannon entropy of these counts of `total` symbols, in bits per symbol.
+    ///
+    /// returns NaN if `total` is zero.
+    pub fn shannon(&self, total: u64) -> Dec {
+        match self {
+            Self::Dense(counts) => shannon::shannon_of_bins(counts, counts.len(), total),
+            Self::Sparse(counts) => {
+                if total == 0 {
+                    return Dec::NAN;
+                }
+                counts.values().fold(Dec::ZERO, |entropy, &count| { entropy.add(shannon::entropy_term(count, total)) })
+            },
+        }
+    }
+
+    /// the number of distinct symbols observed.
+    pub fn observed(&self) -> u64 {
+        match self {
+            Self::Dense(counts) => counts.iter().filter(|count| { **count > 0 }).count() as u64,
+            Self::Sparse(counts) => counts.len() as u64,
+        }
+    }
+}
+
+/// SymbolCounts is occurrence counts of symbols of any width in `1..=MAX_SYMBOL_BITS`,
+/// that is counted by [ChiSquareCalculation], [MeanCalculation] and [ShannonCalculation] instead of [Buckets].
+///
+/// the input is a stream of bits, and every `bits` bits is a symbol. with [BitOrder::MsbFirst] (the default),
+/// the first bit is the most significant bit of symbol, so 16-bit symbols are big-endian;
+/// with [BitOrder::LsbFirst], the first bit is the least significant bit, so 16-bit symbols are little-endian.
+/// bits of the last incomplete symbol are ignored.
+///
+/// 8-bit symbols are the same as [Mode::Bytes], and 1-bit symbols are the same as [Mode::Bits].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolCounts {
+    /// width of every symbol
+    bits: u32,
+    /// order of bits
+    order: BitOrder,
+    /// occurrence counts
+    table: SymbolTable,
+    /// bits of the incomplete symbol
+    pending: u64,
+    /// the number of bits in `pending`
+    pending_len: u32,
+}
+
+impl SymbolCounts {
+    /// create empty counts of symbols of `bits` wide.
+    ///
+    /// # Panics
+    /// if `bits` is not valid, see [SymbolCounts::is_valid].
+    pub fn new(bits: u32) -> Self {
+        assert!(Self::is_valid(bits), "invalid symbol width");
+        Self {
+            bits,
+            order: BitOrder::MsbFirst,
+            table: SymbolTable::new(bits),
+            pending: 0,
+            pending_len: 0,
+        }
+    }
+
+    /// set the bit order, that should be used on empty counts.
+    #[inline(always)]
+    pub fn with_order(mut self, order: BitOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// checks whether `bits` is in `1..=MAX_SYMBOL_BITS`.
+    #[inline(always)]
+    pub const fn is_valid(bits: u32) -> bool {
+        bits >= 1 && bits <= MAX_SYMBOL_BITS
+    }
+
+    /// get the width of symbols.
+    #[inline(always)]
+    pub const fn sample_bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// get the bit order of symbols.
+    #[inline(always)]
+    pub const fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// the number of possible symbol values (`2^bits`).
+    #[inline(always)]
+    pub const fn bins(&self) -> u64 {
+        1 << self.bits
+    }
+
+    /// get the occurrence counts of symbols.
+    #[inline(always)]
+    pub const fn table(&self) -> &SymbolTable {
+        &self.table
+    }
+}
+
+impl Counts for SymbolCounts {
+    #[inline(always)]
+    fn bins(&self) -> u64 {
+        Self::bins(self)
+    }
+
+    #[inline(always)]
+    fn sample_bits(&self) -> u32 {
+        Self::sample_bits(self)
+    }
+
+    #[inline(always)]
+    fn count(&self, index: u64) -> u64 {
+        self.table.count(index)
+    }
+
+    #[inline(always)]
+    fn max_count(&self) -> u64 {
+        self.table.iter().map(|(_, count)| { count }).max().unwrap_or(0)
+    }
+
+    /// count every symbol of `bytes`, then returns the number of symbols.
+    fn tally(&mut self, bytes: &[u8]) -> u64 {
+        let mask = self.bins() - 1;
+        let mut total = 0;
+        for &byte in bytes {
+            // at most `bits - 1 + 8` bits are pending, that fits in u64.
+            match self.order {
+                BitOrder::MsbFirst => {
+                    self.pending = (self.pending << 8) | (byte as u64);
+                    self.pending_len += 8;
+                    while self.pending_len >= self.bits {
+                        self.pending_len -= self.bits;
+                        self.table.add((self.pending >> self.pending_len) & mask);
+                        total += 1;
+                    }
+                    self.pending &= (1 << self.pending_len) - 1;
+                },
+                BitOrder::LsbFirst => {
+                    self.pending |= (byte as u64) << self.pending_len;
+                    self.pending_len += 8;
+                    while self.pending_len >= self.bits {
+                        self.table.add(self.pending & mask);
+                        total += 1;
+                        self.pending >>= self.bits;
+                        self.pending_len -= self.bits;
+                    }
+                },
+            }
+        }
+        total
+    }
+
+    /// chi-square statistic of these counts of `total` symbols, see [chisqr::chi_statistic_bins] and [chisqr::chi_statistic_sum_squares].
+    fn chi(&self, total: u64) -> Dec {
+        match self.table {
+            SymbolTable::Dense(ref counts) => chisqr::chi_statistic_bins(counts, counts.len(), total),
+            SymbolTable::Sparse(ref counts) => {
+                let sum_squares =
+                    counts.values().fold(Dec::ZERO, |sum, &count| {
+                        let count = Dec::from_u64(count);
+                        sum.add(count.mul(count))
+                    });
+                chisqr::chi_statistic_sum_squares(sum_squares, Dec::from_u64(self.bins()), total)
+            },
+        }
+    }
+
+    /// arithmetic mean of these counts of `total` symbols, see [mean::mean_of_bins].
+    fn mean(&self, total: u64) -> Dec {
+        match self.table {
+            SymbolTable::Dense(ref counts) => mean::mean_of_bins(counts, counts.len(), total),
+            SymbolTable::Sparse(ref counts) => {
+                if total == 0 {
+                    return Dec::NAN;
+                }
+                let sum = counts.iter().fold(Dec::ZERO, |sum, (&symbol, &count)| { sum.add(Dec::from_u64(symbol).mul(Dec::from_u64(count))) });
+                sum.div(Dec::from_u64(total))
+            },
+        }
+    }
+
+    /// shannon entropy of these counts of `total` symbols, in bits per symbol (at most `bits`).
+    #[inline(always)]
+    fn shannon(&self, total: u64) -> Dec {
+        self.table.shannon(total)
+    }
+}
+
+/// SymbolResult is the results of chi-square, mean and shannon calculations of the same symbol counts, like [EntestResult] of bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolResult {
+    bits: u32,
+    samples: u64,
+    observed: u64,
+    chi: Dec,
+    chi_prob: Dec,
+    mean: Dec,
+    mean_z_score: Dec,
+    mean_p_value: Dec,
+    shannon: Dec,
+}
+
+impl SymbolResult {
+    /// get the results of chi-square calculation of symbols, and the mean and shannon calculations of the same counts.
+    pub fn new(chi: ChiSquareCalculation<SymbolCounts>) -> Self {
+        let (chi_sq, chi_prob) = chi.finalize_probability();
+        let counts = chi.counts();
+        let (bits, observed) = (counts.sample_bits(), counts.table().observed());
+        let mean = MeanCalculation::from(chi.clone());
+        let shannon = ShannonCalculation::from(chi);
+        Self {
+            bits,
+            samples: mean.samples(),
+            observed,
+            chi: chi_sq,
+            chi_prob,
+            mean: mean.finalize(),
+            mean_z_score: mean.z_score(),
+            mean_p_value: mean.p_value(),
+            shannon: shannon.finalize(),
+        }
+    }
+
+    /// get the width of symbols.
+    #[inline(always)]
+    pub const fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// the number of possible symbol values (`2^bits`).
+    #[inline(always)]
+    pub const fn bins(&self) -> u64 {
+        1 << self.bits
+    }
+
+    /// get the number of symbols.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// the number of distinct symbols observed.
+    #[inline(always)]
+    pub const fn observed(&self) -> u64 {
+        self.observed
+    }
+
+    /// result of chi-square calculation.
+    #[inline(always)]
+    pub const fn chi(&self) -> Dec {
+        self.chi
+    }
+
+    /// probability of a random dataset exceeds [SymbolResult::chi], with `bins - 1` degrees of freedom.
+    #[inline(always)]
+    pub const fn chi_prob(&self) -> Dec {
+        self.chi_prob
+    }
+
+    /// result of mean calculation, that is expected to be `(2^bits - 1) / 2` for random input.
+    #[inline(always)]
+    pub const fn mean(&self) -> Dec {
+        self.mean
+    }
+
+    /// the expected arithmetic mean of random symbols.
+    #[inline(always)]
+    pub const fn expected_mean(&self) -> Dec {
+        Dec::from_u64(self.bins() - 1).div(chisqr::TWO)
+    }
+
+    /// standard score of the mean, see [MeanCalculation::z_score].
+    #[inline(always)]
+    pub const fn mean_z_score(&self) -> Dec {
+        self.mean_z_score
+    }
+
+    /// two-sided p-value of [SymbolResult::mean_z_score].
+    #[inline(always)]
+    pub const fn mean_p_value(&self) -> Dec {
+        self.mean_p_value
+    }
+
+    /// result of shannon calculation, in bits per symbol (at most `bits`).
+    #[inline(always)]
+    pub const fn shannon(&self) -> Dec {
+        self.shannon
+    }
+
+    /// the outcome of one test, or None if it is not computed for symbols (monte-carlo and serial correlation).
+    pub const fn outcome(&self, kind: TestKind) -> Option<TestOutcome> {
+        match kind {
+            TestKind::Entropy => Some(TestOutcome::new(self.shannon, Dec::from_u32(self.bits), self.samples)),
+            TestKind::ChiSquare => {
+                Some(
+                    TestOutcome::new(self.chi, Dec::from_u64(self.bins() - 1), self.samples)
+                        .with_p_value(self.chi_prob)
+                        .with_both_tails(true)
+                )
+            },
+            TestKind::Mean => Some(TestOutcome::new(self.mean, self.expected_mean(), self.samples).with_p_value(self.mean_p_value)),
+            TestKind::MonteCarlo | TestKind::SerialCorrelation => None,
+        }
+    }
+}
+
+/// the results in the same form as [EntestResult].
+impl core::fmt::Display for SymbolResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bits = self.bits;
+        let chi_prob = self.chi_prob.mul(dec!(100));
+        writeln!(f, "Entropy = {} bits per {bits}-bit symbol.", format::CFloat(self.shannon, 6))?;
+        writeln!(f, "{} of {} possible symbols are observed.", self.observed, self.bins())?;
+        writeln!(f)?;
+        writeln!(f, "Chi square distribution for {} samples is {},", self.samples, format::CFloat(self.chi, 2))?;
+        writeln!(f, "and randomly would exceed this value {} percent of the times.", format::CFloat(chi_prob, 2))?;
+        writeln!(f)?;
+        writeln!(f, "Arithmetic mean value of data symbols is {} ({} = random).", format::CFloat(self.mean, 4), self.expected_mean())?;
+        writeln!(
+            f, "The deviation is {} standard errors (p-value {}).",
+            format::CFloat(self.mean_z_score, 2), format::CFloat(self.mean_p_value, 4),
+        )
+    }
+}
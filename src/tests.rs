@@ -36,9 +36,1004 @@ fn predefined() {
     let ret = dbg!(Entest::test(predefined_data_buf));
     assert_eq!(ret.samples(), LEN as u64);
     assert_eq!(ret.chi(), &dec!(293.0));
-    assert_eq!(ret.chi_prob(), &dec!(0.05104028823948067937));
+    assert_eq!(ret.chi_prob(), &dec!(0.05104029153024094444));
     assert_eq!(ret.mc(), &dec!(3.146541617819460727));
     assert_eq!(ret.mean(), &dec!(128.2646484375));
-    assert_eq!(ret.sc(), &dec!(0.014631937126484728929));
+    assert_eq!(ret.sc(), &dec!(0.014354755680452154351));
     assert_eq!(ret.shannon(), &dec!(7.979134079303539237));
 }
+
+#[test]
+fn bits_alternating() {
+    let alternating_buf = [0x55u8; LEN];
+    let ret = dbg!(Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), &alternating_buf));
+    assert_eq!(ret.samples(), (LEN * 8) as u64);
+    assert_eq!(ret.chi(), &dec!(0.0));
+    assert_eq!(ret.chi_prob(), &dec!(1.0));
+    assert_eq!(ret.mean(), &dec!(0.5));
+    assert_eq!(ret.sc(), &dec!(-1.0));
+    assert_eq!(ret.shannon(), &dec!(1.0));
+}
+
+#[test]
+fn bits_order() {
+    let predefined_data_buf = include_bytes!("tests.rand");
+
+    let msb = dbg!(Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), predefined_data_buf));
+    let lsb = dbg!(Entest::test_mode(Mode::Bits(BitOrder::LsbFirst), predefined_data_buf));
+    assert_eq!(msb.samples(), (LEN * 8) as u64);
+    assert_eq!(lsb.samples(), (LEN * 8) as u64);
+
+    // counting bits does not depend on bit order.
+    assert_eq!(msb.chi(), lsb.chi());
+    assert_eq!(msb.mean(), lsb.mean());
+    assert_eq!(msb.shannon(), lsb.shannon());
+    assert_ne!(msb.sc(), lsb.sc());
+
+    // like `ent -b`, MSB-first monte-carlo coordinates are the same as byte mode.
+    assert_eq!(msb.mc(), Entest::test(predefined_data_buf).mc());
+    assert_ne!(msb.mc(), lsb.mc());
+}
+
+#[test]
+fn histogram() {
+    let predefined_data_buf = include_bytes!("tests.rand");
+
+    let ret = Entest::test(predefined_data_buf);
+    let hist = ret.histogram();
+    assert_eq!(hist.bins(), 256);
+    assert_eq!(hist.total(), LEN as u64);
+    assert_eq!(hist.counts().iter().sum::<u64>(), LEN as u64);
+    assert_eq!(hist.missing_count(), 0);
+    assert_eq!(hist.most_common(), Some((168, 61)));
+    assert_eq!(hist.least_common(), Some((25, 20)));
+    assert_eq!(hist.fraction(168), dec!(61) / dec!(10240));
+
+    let zero = Entest::test(&[0u8; LEN]);
+    let hist = zero.histogram();
+    assert_eq!(hist.missing_count(), 255);
+    assert_eq!(hist.missing().next(), Some(1));
+    assert_eq!(hist.most_common(), Some((0, LEN as u64)));
+    assert_eq!(hist.least_common(), Some((0, LEN as u64)));
+
+    let bits = Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), &[0x0fu8; LEN]);
+    let hist = bits.histogram();
+    assert_eq!(hist.bins(), 2);
+    assert_eq!(hist.counts(), &[(LEN * 4) as u64, (LEN * 4) as u64]);
+
+    let empty = Entest::test(&[]);
+    assert_eq!(empty.histogram().most_common(), None);
+    assert_eq!(empty.histogram().least_common(), None);
+}
+
+#[test]
+fn fold_case() {
+    let mut text = *b"Hello, WORLD! \xc9\xd7\xdf";
+    FoldCase::Iso8859_1.then(transform::Identity).transform(&mut text);
+    assert_eq!(&text, b"hello, world! \xe9\xd7\xdf");
+
+    let mut text = *b"Hello, WORLD! \xc9\xd7\xdf";
+    Some(FoldCase::Ascii).transform(&mut text);
+    assert_eq!(&text, b"hello, world! \xc9\xd7\xdf");
+
+    let mut text = *b"Hello, WORLD!";
+    None::<FoldCase>.transform(&mut text);
+    assert_eq!(&text, b"Hello, WORLD!");
+}
+
+#[cfg(feature="std")]
+#[test]
+fn fold_case_reader() {
+    let text = b"The Quick Brown Fox Jumps Over The Lazy Dog";
+    let mut reader = transform::TransformReader::new(&text[..], FoldCase::default());
+    let folded = Entest::from_reader(Mode::Bytes, &mut reader).unwrap();
+    let expected = Entest::test(b"the quick brown fox jumps over the lazy dog");
+    assert_eq!(folded.histogram().counts(), expected.histogram().counts());
+    assert_eq!(folded.sc(), expected.sc());
+}
+
+#[test]
+fn terse() {
+    let predefined_data_buf = include_bytes!("tests.rand");
+
+    let ret = Entest::test(predefined_data_buf);
+    assert_eq!(
+        ret.terse(false).to_string(),
+        "0,File-bytes,Entropy,Chi-square,Mean,Monte-Carlo-Pi,Serial-Correlation\n\
+         1,10240,7.979134,293.000000,128.264648,3.146542,0.014355\n"
+    );
+
+    let terse = ret.terse(true).to_string();
+    let lines: Vec<&str> = terse.lines().collect();
+    assert_eq!(lines.len(), 2 + 1 + 256);
+    assert_eq!(lines[2], "2,Value,Occurrences,Fraction");
+    assert_eq!(lines[3 + 168], "3,168,61,0.005957");
+
+    let bits = Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), &[0u8; LEN]).terse(true).to_string();
+    assert!(bits.starts_with("0,File-bits,"));
+    assert!(bits.ends_with("3,0,81920,1.000000\n3,1,0,0.000000\n"));
+}
+
+#[test]
+fn ent_corpus() {
+    let rand = include_bytes!("tests.rand");
+    let zeros = include_bytes!("../tests/corpus/zeros.bin");
+    let counter = include_bytes!("../tests/corpus/counter.bin");
+    let text = include_bytes!("../tests/corpus/text.txt");
+    let short = include_bytes!("../tests/corpus/short.bin");
+    let bits = Mode::Bits(BitOrder::MsbFirst);
+
+    // (input, mode, counts, fold case, expected output of `ent`)
+    type Case<'a> = (&'a [u8], Mode, bool, bool, &'a [u8]);
+    let corpus: [Case<'_>; 12] = [
+        (rand, Mode::Bytes, false, false, include_bytes!("../tests/corpus/rand.ent")),
+        (rand, bits, false, false, include_bytes!("../tests/corpus/rand.b.ent")),
+        (rand, Mode::Bytes, true, false, include_bytes!("../tests/corpus/rand.c.ent")),
+        (zeros, Mode::Bytes, false, false, include_bytes!("../tests/corpus/zeros.ent")),
+        (zeros, bits, false, false, include_bytes!("../tests/corpus/zeros.b.ent")),
+        (counter, Mode::Bytes, false, false, include_bytes!("../tests/corpus/counter.ent")),
+        (counter, Mode::Bytes, true, false, include_bytes!("../tests/corpus/counter.c.ent")),
+        (counter, bits, true, false, include_bytes!("../tests/corpus/counter.bc.ent")),
+        (text, Mode::Bytes, false, false, include_bytes!("../tests/corpus/text.ent")),
+        (text, Mode::Bytes, false, true, include_bytes!("../tests/corpus/text.f.ent")),
+        (text, Mode::Bytes, true, true, include_bytes!("../tests/corpus/text.cf.ent")),
+        (short, Mode::Bytes, false, false, include_bytes!("../tests/corpus/short.ent")),
+    ];
+
+    for (i, (input, mode, counts, fold, expected)) in corpus.into_iter().enumerate() {
+        let mut input = input.to_vec();
+        if fold {
+            FoldCase::Iso8859_1.transform(&mut input);
+        }
+        let ret = Entest::test_mode(mode, &input);
+
+        // Display writes characters of the table as UTF-8 instead of ISO 8859-1.
+        let latin1: String = expected.iter().map(|&b| { char::from(b) }).collect();
+        assert_eq!(ret.ent(counts).to_string(), latin1, "corpus #{i}");
+
+        #[cfg(feature="std")]
+        {
+            let mut out = Vec::new();
+            ret.ent(counts).write_to(&mut out).unwrap();
+            assert_eq!(out, expected, "corpus #{i}");
+        }
+    }
+}
+
+#[test]
+fn byte_range() {
+    let range = ByteRange::ALL.with_offset(512).with_length(1024);
+    assert_eq!(range.end(), Some(1536));
+    assert_eq!(range.to_string(), "512..1536");
+    assert_eq!(ByteRange::new(7, None).to_string(), "7..");
+    assert!(ByteRange::default().is_all());
+
+    let ret = Entest::test(&[1, 2, 3]);
+    assert_eq!(ret.range(), ByteRange::new(0, Some(3)));
+}
+
+#[cfg(feature="std")]
+#[test]
+fn byte_range_reader() {
+    let data = include_bytes!("tests.rand");
+    let expected = Entest::test(&data[512..1536]);
+
+    let range = ByteRange::new(512, Some(1024));
+    let seeked = Entest::from_seekable_range(Mode::Bytes, &mut std::io::Cursor::new(&data[..]), range).unwrap();
+    // a slice is not seekable, so bytes are discarded.
+    let skipped = Entest::from_reader_range(Mode::Bytes, &mut &data[..], range).unwrap();
+    for ret in [seeked, skipped] {
+        assert_eq!(ret.range(), range);
+        assert_eq!(ret.histogram().counts(), expected.histogram().counts());
+        assert_eq!(ret.sc(), expected.sc());
+    }
+
+    // the length is shorter if EOF is reached.
+    let tail = Entest::from_reader_range(Mode::Bytes, &mut &data[..], ByteRange::new(10000, Some(1024))).unwrap();
+    assert_eq!(tail.range(), ByteRange::new(10000, Some(240)));
+
+    let beyond = Entest::from_seekable_range(Mode::Bytes, &mut std::io::Cursor::new(&data[..]), ByteRange::new(20000, None)).unwrap();
+    assert_eq!(beyond.range(), ByteRange::new(20000, Some(0)));
+}
+
+#[test]
+fn chi_square_survival() {
+    use chisqr::{chi_square_survival, gamma_p, gamma_q, ln_gamma};
+
+    // references are computed by mpmath.
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().le(&b.abs().mul(dec!(1e-15))) };
+
+    let lg = [
+        (dec!(0.5), dec!(0.5723649429247000871)),
+        (dec!(3), dec!(0.6931471805599453094)),
+        (dec!(7.25), dec!(7.052185450738539445)),
+        (dec!(127.5), dec!(489.1303704306428126)),
+    ];
+    for (a, expected) in lg {
+        assert!(close(ln_gamma(a), expected), "ln_gamma({a}) = {}", ln_gamma(a));
+    }
+
+    let cases = [
+        (1, dec!(0.34), dec!(0.5598292219170769948)),
+        (1, dec!(0.015494), dec!(0.9009392750244663331)),
+        (2, dec!(3), dec!(0.2231301601484298289)),
+        (10, dec!(5.5), dec!(0.8553785061754919994)),
+        (255, dec!(200), dec!(0.9954254445419518953)),
+        (255, dec!(293), dec!(0.05104029153024094436)),
+        (255, dec!(350), dec!(0.00007132745072663465808)),
+        (4095, dec!(4000), dec!(0.8532760854023797081)),
+        (65535, dec!(66000), dec!(0.09970784924015068428)),
+    ];
+    for (df, chi, expected) in cases {
+        let q = chi_square_survival(chi, df);
+        assert!(close(q, expected), "df={df} chi={chi}: {q}");
+        let p = gamma_p(Dec::from_u64(df).div(dec!(2)), chi.div(dec!(2)));
+        assert!(p.add(q).sub(dec!(1)).abs().lt(&dec!(1e-17)));
+    }
+
+    assert_eq!(chi_square_survival(dec!(0), 255), dec!(1));
+    assert_eq!(chi_square_survival(dec!(1000000), 255), dec!(0));
+    assert!(chi_square_survival(Dec::NAN, 255).is_nan());
+    assert!(chi_square_survival(dec!(1), 0).is_nan());
+    assert!(gamma_q(dec!(-1), dec!(1)).is_nan());
+
+    // the approximation for large df is close to the exact value (computed by mpmath) already at 2^20.
+    let wh = chisqr::chi_square_survival_wh(dec!(1050024), 1 << 20);
+    assert!(wh.sub(dec!(0.1586810013296502060)).abs().lt(&dec!(1e-8)), "{wh}");
+    assert_eq!(chi_square_survival(dec!(4294967295), 4294967295), chisqr::chi_square_survival_wh(dec!(4294967295), 4294967295));
+
+    // the approximation of ent is close to the exact value, for the degrees of freedom of bytes and bits.
+    for (df, chi, expected) in cases {
+        if df == 1 || df == 255 {
+            let ent = chisqr::ent_probability_chi_sq(chi, df as u16);
+            assert!(ent.sub(expected).abs().lt(&dec!(1e-6)), "df={df} chi={chi}: {ent}");
+        }
+    }
+    assert_eq!(chisqr::ent_probability_chi_sq(dec!(293), 255), chisqr::probability_chi_sq(dec!(293), 127));
+}
+
+#[test]
+fn outcomes() {
+    let data = include_bytes!("tests.rand");
+    let mut entest = Entest::new();
+    entest.update(data);
+    let ret = entest.finalize();
+
+    let outcomes = ret.outcomes();
+    assert_eq!(outcomes.map(|(kind, _)| { kind }), TestKind::ALL);
+    assert_eq!(outcomes[0].1, entest.shannon.outcome());
+    assert_eq!(outcomes[1].1, entest.chi.outcome());
+    assert_eq!(outcomes[2].1, entest.mean.outcome());
+    assert_eq!(outcomes[3].1, EntropyTest::outcome(&entest.mc));
+    assert_eq!(outcomes[4].1, entest.sc.outcome());
+
+    let chi = ret.outcome(TestKind::ChiSquare);
+    assert_eq!(chi.expected, dec!(255));
+    assert_eq!(chi.samples, 10240);
+    assert_eq!(chi.verdict(dec!(0.01)), Some(TestVerdict::Pass));
+    // two-tailed p-value is about 0.102.
+    assert_eq!(chi.verdict(dec!(0.05)), Some(TestVerdict::Weak));
+    assert_eq!(chi.verdict(dec!(0.2)), Some(TestVerdict::Fail));
+    assert_eq!(ret.outcome(TestKind::MonteCarlo).samples, 10240 / 6);
+    assert_eq!(ret.outcome(TestKind::Entropy).verdict(dec!(0.01)), None);
+
+    // too good to be random.
+    let counter: Vec<u8> = (0..=255u8).cycle().take(LEN).collect();
+    let chi = Entest::test(&counter).outcome(TestKind::ChiSquare);
+    assert_eq!(chi.verdict(dec!(0.01)), Some(TestVerdict::Fail));
+
+    let empty = Entest::test(&[]).outcome(TestKind::ChiSquare);
+    assert_eq!(empty.verdict(dec!(0.01)), Some(TestVerdict::Fail));
+}
+
+#[test]
+fn mc_significance() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+
+    let ret = Entest::test(include_bytes!("tests.rand"));
+    assert_eq!((ret.mc_in_count(), ret.mc_tries()), (1342, 1706));
+
+    // references are computed by mpmath, with the probability of 24-bit lattice.
+    assert!(close(ret.mc_z_score(), dec!(0.1244784283165873503)));
+    assert!(close(ret.mc_p_value(), dec!(0.9009364792391334702)));
+    // every point is inside of all-zero data, that is 4 with the correction of 24-bit lattice.
+    assert!(close(Entest::test(&[0u8; 60]).mc_corrected(), dec!(4.000000173273201956)));
+    assert!(close(ret.mc_corrected(), dec!(3.146541754122296028)));
+    let (low, high) = ret.mc_confidence_interval(chisqr::NORMAL_Z_95);
+    assert!(close(low, dec!(3.066248711701194117)));
+    assert!(close(high, dec!(3.221682982520951607)));
+    assert_eq!(ret.outcome(TestKind::MonteCarlo).verdict(dec!(0.01)), Some(TestVerdict::Pass));
+
+    // the same error is significant for large input.
+    let z = mc::pi_z_score(1342 * 100000, 1706 * 100000);
+    assert!(z.gt(&dec!(39)));
+    assert!(mc::pi_p_value(1342 * 100000, 1706 * 100000).lt(&dec!(1e-300)));
+
+    assert!(Entest::test(&[]).mc_z_score().is_nan());
+    assert!(Entest::test(&[]).mc_confidence_interval(chisqr::NORMAL_Z_99).0.is_nan());
+}
+
+#[test]
+fn mc_geometry() {
+    let close = |a: Dec, b: Dec, eps: Dec| { a.sub(b).abs().lt(&eps) };
+    let data = include_bytes!("tests.rand");
+
+    // the default is the same as ent.
+    let ent = MonteCarloCalculation::new().with_geometry(MonteCarloGeometry::ENT);
+    assert_eq!(ent.geometry(), MonteCarloGeometry::default());
+    assert_eq!(MonteCarloCalculation::test(data), *Entest::test(data).mc());
+
+    // volume of n-ball.
+    assert!(close(mc::ball_probability(2), Dec::PI.div(dec!(4)), dec!(1e-18)));
+    assert!(close(mc::ball_probability(3), Dec::PI.div(dec!(6)), dec!(1e-18)));
+    for dimensions in 2..=mc::MAX_DIMENSIONS {
+        let pi = mc::pi_from_fraction(mc::ball_probability(dimensions), dimensions);
+        assert!(close(pi, Dec::PI, dec!(1e-17)), "dimensions={dimensions}: {pi}");
+    }
+
+    // the estimate is corrected for the lattice, so the expected fraction of a perfect source gives pi.
+    for geometry in [MonteCarloGeometry::new(8, 2), MonteCarloGeometry::new(8, 8), MonteCarloGeometry::ENT] {
+        let pi = geometry.pi_from_fraction(geometry.probability());
+        assert!(close(pi, Dec::PI, dec!(1e-15)), "{geometry}: {pi}");
+    }
+    let mut mc = MonteCarloCalculation::new().with_geometry(MonteCarloGeometry::new(8, 8));
+    mc.update(data);
+    let (low, high) = mc.confidence_interval(chisqr::NORMAL_Z_95);
+    assert!(low.lt(&mc.corrected()) && mc.corrected().lt(&high));
+    assert_ne!(mc.corrected(), mc.finalize());
+
+    // the exact probability of 8-bit lattice in 3D is 0.5220657587051392 (by counting points).
+    let p = MonteCarloGeometry::new(8, 3).probability();
+    assert!(close(p, dec!(0.5220657587051392), dec!(2e-6)), "{p}");
+
+    // (geometry, inside, tries, z-score) computed by python and mpmath.
+    let cases = [
+        (MonteCarloGeometry::new(8, 3), 1750, 3413, dec!(-1.089895137606363701)),
+        (MonteCarloGeometry::new(16, 4), 378, 1280, dec!(-1.015564246860708400)),
+        (MonteCarloGeometry::new(32, 2), 980, 1280, dec!(-1.723135695502036443)),
+    ];
+    for (geometry, in_count, tries, z) in cases {
+        let mut mc = MonteCarloCalculation::new().with_geometry(geometry);
+        for part in data.chunks(1000) {
+            mc.update(part);
+        }
+        assert_eq!((mc.in_count(), mc.tries()), (in_count, tries), "{geometry}");
+        assert!(close(mc.z_score(), z, dec!(1e-15)), "{geometry}: {}", mc.z_score());
+        assert_ne!(mc.outcome().verdict(dec!(0.01)), Some(TestVerdict::Fail));
+    }
+
+    let mut entest = Entest::new();
+    entest.set_mc_geometry(MonteCarloGeometry::new(16, 4)).update(data);
+    let ret = entest.finalize();
+    assert_eq!(ret.mc_geometry(), MonteCarloGeometry::new(16, 4));
+    assert!(close(ret.mc_z_score(), cases[1].3, dec!(1e-15)));
+
+    // points on the circle are inside, like ent. only the first two points are on it.
+    let mut boundary = MonteCarloCalculation::new().with_geometry(MonteCarloGeometry::new(8, 2));
+    boundary.update(&[255, 0, 0, 255, 255, 1, 180, 181]);
+    assert_eq!((boundary.in_count(), boundary.tries()), (2, 4));
+
+    assert!(! MonteCarloGeometry::is_valid(12, 2));
+    assert!(! MonteCarloGeometry::is_valid(8, 1));
+    assert!(! MonteCarloGeometry::is_valid(8, mc::MAX_DIMENSIONS + 1));
+}
+
+#[test]
+fn mean_significance() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed by mpmath.
+    let ret = Entest::test(data);
+    assert!(close(ret.mean_z_score(), dec!(1.047045982806941393)));
+    assert!(close(ret.mean_p_value(), dec!(0.2950783708449192217)));
+    assert_eq!(ret.mean_z_score(), MeanCalculation::new().update(data).z_score());
+
+    let bits = Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), data);
+    assert!(close(bits.mean_z_score(), dec!(0.5869678440936947954)));
+    assert!(close(bits.mean_p_value(), dec!(0.5572253031825258428)));
+
+    assert_eq!(mean::uniform_variance(256), dec!(5461.25));
+    assert_eq!(mean::uniform_variance(2), dec!(0.25));
+
+    // biased generator: every byte is in 0..240.
+    let biased: Vec<u8> = (0..LEN).map(|i| { (i % 240) as u8 }).collect();
+    let ret = Entest::test(&biased);
+    assert!(ret.mean_z_score().lt(&dec!(-2)));
+    assert_eq!(ret.outcome(TestKind::Mean).verdict(dec!(0.05)), Some(TestVerdict::Fail));
+
+    assert!(Entest::test(&[]).mean_p_value().is_nan());
+}
+
+#[test]
+fn sc_definition() {
+    // the reference definition: u[i] is paired with u[(i + 1) % n].
+    fn reference(samples: &[u8]) -> Dec {
+        let n = samples.len() as i64;
+        let (mut t1, mut t2, mut t3) = (0i64, 0i64, 0i64);
+        for (i, &u) in samples.iter().enumerate() {
+            let u = u as i64;
+            t1 += u * (samples[(i + 1) % samples.len()] as i64);
+            t2 += u;
+            t3 += u * u;
+        }
+        let den = n * t3 - t2 * t2;
+        if den == 0 {
+            return Dec::NAN;
+        }
+        Dec::from_i64(n * t1 - t2 * t2).div(Dec::from_i64(den))
+    }
+
+    let data = include_bytes!("tests.rand");
+    for len in 1..64 {
+        let input = &data[..len];
+        let expected = reference(input);
+        let actual = *Entest::test(input).sc();
+        assert!(expected.is_nan() && actual.is_nan() || expected == actual, "len={len}: {actual} != {expected}");
+
+        let bits: Vec<u8> = input.iter().flat_map(|&b| { BitOrder::MsbFirst.expand(b) }).collect();
+        let expected = reference(&bits);
+        let actual = *Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), input).sc();
+        assert!(expected.is_nan() && actual.is_nan() || expected == actual, "bits len={len}: {actual} != {expected}");
+    }
+
+    // the result does not depend on how the input is split.
+    let whole = SerialCorrelationCoefficientCalculation::test(data);
+    for chunk in [1, 2, 3, 5, 7, 64, 1000, 4099] {
+        let mut sc = SerialCorrelationCoefficientCalculation::new();
+        for part in data.chunks(chunk) {
+            sc.update(part);
+        }
+        assert_eq!(sc.finalize(), whole, "chunk={chunk}");
+    }
+
+    let bits = Mode::Bits(BitOrder::LsbFirst);
+    let whole = *Entest::test_mode(bits, data).sc();
+    for chunk in [1, 3, 1000] {
+        let mut sc = SerialCorrelationCoefficientCalculation::with_mode(bits);
+        for part in data.chunks(chunk) {
+            sc.update(part);
+        }
+        assert_eq!(sc.finalize(), whole, "bits chunk={chunk}");
+    }
+
+    // every split of a short input, including the first sample alone and empty updates.
+    let input = &data[..17];
+    let expected = reference(input);
+    for at in 0..=input.len() {
+        let mut sc = SerialCorrelationCoefficientCalculation::new();
+        sc.update(&input[..at]).update(&[]).update(&input[at..]);
+        assert_eq!(sc.finalize(), expected, "split at {at}");
+    }
+}
+
+#[test]
+fn sc_significance() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+
+    // references are computed by mpmath.
+    let ret = Entest::test(include_bytes!("tests.rand"));
+    assert!(close(ret.sc_z_score(), dec!(1.462196591321850475)));
+    assert!(close(ret.sc_p_value(), dec!(0.1436873454602624220)));
+    let (low, high) = ret.sc_band();
+    assert!(close(low, dec!(-0.01986576202576182173)));
+    assert!(close(high, dec!(0.01967043045041266654)));
+    assert_eq!(ret.outcome(TestKind::SerialCorrelation).verdict(dec!(0.01)), Some(TestVerdict::Pass));
+
+    // small inputs.
+    assert!(sc::sc_band(2).0.is_nan());
+    assert_eq!(sc::sc_expected(2), dec!(-1));
+
+    // highly correlated.
+    let ramp: Vec<u8> = (0..LEN).map(|i| { (i / 40) as u8 }).collect();
+    let ret = Entest::test(&ramp);
+    assert!(ret.sc().gt(&ret.sc_band().1));
+    assert_eq!(ret.outcome(TestKind::SerialCorrelation).verdict(dec!(0.01)), Some(TestVerdict::Fail));
+}
+
+#[test]
+fn autocorrelation() {
+    // the reference definition: u[i] is paired with u[(i + lag) % n].
+    fn reference(samples: &[u8], lag: usize) -> Dec {
+        let n = samples.len() as i64;
+        if samples.len() <= lag {
+            return Dec::NAN;
+        }
+        let (mut t1, mut t2, mut t3) = (0i64, 0i64, 0i64);
+        for (i, &u) in samples.iter().enumerate() {
+            let u = u as i64;
+            t1 += u * (samples[(i + lag) % samples.len()] as i64);
+            t2 += u;
+            t3 += u * u;
+        }
+        let den = n * t3 - t2 * t2;
+        if den == 0 {
+            return Dec::NAN;
+        }
+        Dec::from_i64(n * t1 - t2 * t2).div(Dec::from_i64(den))
+    }
+
+    let data = include_bytes!("tests.rand");
+    let lags = [1, 2, 3, 8, 16, 1000, 1024];
+    for len in [1, 2, 9, 17, 1001, 1025, 4099, data.len()] {
+        let input = &data[..len];
+        let mut ac = AutocorrelationCalculation::new(&lags);
+        ac.update(input);
+        for (index, &lag) in lags.iter().enumerate() {
+            let expected = reference(input, lag as usize);
+            let actual = ac.coefficient(index);
+            assert!(expected.is_nan() && actual.is_nan() || expected == actual, "len={len} lag={lag}: {actual} != {expected}");
+        }
+    }
+
+    // lag 1 is the serial correlation coefficient, and the result does not depend on how the input is split.
+    let sc = Entest::test(data);
+    let whole = AutocorrelationCalculation::powers_of_two(Mode::Bytes, 512).update(data).outcome(0);
+    assert_eq!(whole.statistic, *sc.sc());
+    assert_eq!(whole.p_value, Some(sc.sc_p_value()));
+    for chunk in [1, 7, 1000, 4099] {
+        let mut ac = AutocorrelationCalculation::powers_of_two(Mode::Bytes, 512);
+        for part in data.chunks(chunk) {
+            ac.update(part);
+        }
+        assert_eq!(ac.outcome(0), whole, "chunk={chunk}");
+    }
+
+    // a period of 8 is found at lag 8 only.
+    let mut periodic = data.to_vec();
+    for i in 0..periodic.len() {
+        if (i / 8) % 2 == 1 {
+            periodic[i] = periodic[i - 8];
+        }
+    }
+    let mut ac = AutocorrelationCalculation::lag_range(Mode::Bytes, 1, 8);
+    ac.update(&periodic);
+    assert_eq!(ac.lags(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+    for (lag, outcome) in ac.outcomes() {
+        let expected = if lag == 8 { TestVerdict::Fail } else { TestVerdict::Pass };
+        assert_eq!(outcome.verdict(dec!(0.001)), Some(expected), "lag={lag}");
+    }
+    assert!(! AutocorrelationCalculation::is_valid_lags(&[0]));
+    assert!(! AutocorrelationCalculation::is_valid_lags(&[autocorr::MAX_LAG as u32 + 1]));
+}
+
+#[test]
+fn symbols() {
+    let data = include_bytes!("tests.rand");
+    let symbols = |bits: u32, order: BitOrder, input: &[u8]| {
+        let mut chi = ChiSquareCalculation::with_symbols(bits, order);
+        for part in input.chunks(3) {
+            chi.update(part);
+        }
+        chi
+    };
+
+    // 8-bit symbols are bytes, and 1-bit symbols are bits.
+    for (bits, mode) in [(8, Mode::Bytes), (1, Mode::Bits(BitOrder::MsbFirst))] {
+        let ret = Entest::test_mode(mode, data);
+        let chi = symbols(bits, BitOrder::MsbFirst, data);
+        let mut mean = MeanCalculation::with_symbols(bits, BitOrder::MsbFirst);
+        mean.update(data);
+        assert_eq!(chi.outcome(), ret.outcome(TestKind::ChiSquare));
+        assert_eq!(mean.outcome(), ret.outcome(TestKind::Mean));
+        assert_eq!(mean.z_score(), ret.mean_z_score());
+        assert_eq!(ShannonCalculation::from(chi.clone()).outcome(), ret.outcome(TestKind::Entropy));
+        let mut buckets = ChiSquareCalculation::with_mode(mode);
+        buckets.update(data);
+        for index in [0, 1, 2, 255, 256] {
+            assert_eq!(chi.counts().count(index), buckets.counts().count(index), "{bits}-bit symbol {index}");
+        }
+
+        let sym = SymbolResult::new(chi);
+        assert_eq!(sym.samples(), ret.samples());
+        assert_eq!(sym.chi(), *ret.chi());
+        assert_eq!(sym.chi_prob(), *ret.chi_prob());
+        assert_eq!(sym.mean(), *ret.mean());
+        assert_eq!(sym.mean_z_score(), ret.mean_z_score());
+        assert_eq!(sym.shannon(), *ret.shannon());
+        assert_eq!(sym.outcome(TestKind::ChiSquare), Some(ret.outcome(TestKind::ChiSquare)));
+        assert_eq!(sym.outcome(TestKind::MonteCarlo), None);
+    }
+
+    // the first bit is the most (or least) significant bit of symbol, incomplete symbol is ignored.
+    let input = [0xab, 0xcd, 0xef, 0x12];
+    let values = |chi: ChiSquareCalculation<SymbolCounts>| { chi.counts().table().iter().map(|(value, _)| { value }).collect::<Vec<u64>>() };
+    assert_eq!(values(symbols(12, BitOrder::MsbFirst, &input)), [0xabc, 0xdef]);
+    assert_eq!(values(symbols(12, BitOrder::LsbFirst, &input)), [0xdab, 0xefc]);
+    assert_eq!(values(symbols(16, BitOrder::MsbFirst, &input)), [0xabcd, 0xef12]);
+    assert_eq!(values(symbols(16, BitOrder::LsbFirst, &input)), [0x12ef, 0xcdab]);
+    assert_eq!(values(symbols(4, BitOrder::MsbFirst, &input[..1])), [0xa, 0xb]);
+    assert_eq!(values(symbols(24, BitOrder::LsbFirst, &input)), [0xefcdab]);
+
+    // wide symbols are sparse, and their chi-square counts unobserved symbols as zero.
+    let chi = symbols(16, BitOrder::MsbFirst, data);
+    assert!(matches!(chi.counts().table(), SymbolTable::Dense(_)));
+    let sum_squares = chi.counts().table().iter().fold(Dec::ZERO, |sum, (_, count)| { sum.add(Dec::from_u64(count * count)) });
+    let sparse_chi = chisqr::chi_statistic_sum_squares(sum_squares, dec!(65536), chi.samples());
+    assert!(sparse_chi.sub(chi.finalize()).abs().lt(&dec!(1e-9)), "{sparse_chi} != {}", chi.finalize());
+
+    let chi = symbols(24, BitOrder::MsbFirst, data);
+    assert!(matches!(chi.counts().table(), SymbolTable::Sparse(_)));
+    assert_eq!(chi.samples(), (LEN / 3) as u64);
+    assert_eq!(chi.counts().table().count(0x6c6ed4), 1);
+    assert_eq!(chi.counts().table().count(0), 0);
+    assert_eq!(MeanCalculation::from(chi).outcome().verdict(dec!(0.01)), Some(TestVerdict::Pass));
+
+    assert!(! SymbolCounts::is_valid(0));
+    assert!(! SymbolCounts::is_valid(symbol::MAX_SYMBOL_BITS + 1));
+}
+
+#[test]
+fn block_entropy() {
+    let data = include_bytes!("tests.rand");
+
+    // the reference definition: shannon entropy of overlapping blocks.
+    fn reference(samples: &[u8], n: usize) -> Dec {
+        let mut counts = std::collections::BTreeMap::new();
+        for block in samples.windows(n) {
+            *counts.entry(block).or_insert(0u64) += 1;
+        }
+        let total = (samples.len() + 1 - n) as u64;
+        counts.values().fold(Dec::ZERO, |sum, &count| { sum.add(shannon::entropy_term(count, total)) })
+    }
+
+    let mut block = BlockEntropyCalculation::new(3);
+    for part in data.chunks(7) {
+        block.update(part);
+    }
+    assert_eq!(block.block_entropy(1), *Entest::test(data).shannon());
+    for n in 1..=3 {
+        assert_eq!(block.block_entropy(n), reference(data, n as usize), "n={n}");
+        assert_eq!(block.blocks(n), (LEN + 1) as u64 - n as u64);
+    }
+
+    let bits: Vec<u8> = data.iter().flat_map(|&b| { BitOrder::MsbFirst.expand(b) }).collect();
+    let mut block = BlockEntropyCalculation::with_range(Mode::Bits(BitOrder::MsbFirst), 2, 8);
+    block.update(data);
+    assert_eq!(block.lengths(), 2..=8);
+    for n in [1, 5, 8] {
+        assert_eq!(block.block_entropy(n), reference(&bits, n as usize), "bits n={n}");
+    }
+    assert!(block.finalize().gt(&dec!(0.99)), "{}", block.finalize());
+
+    // a repeating counter has max shannon entropy, but no entropy rate.
+    let counter: Vec<u8> = (0..LEN).map(|i| { i as u8 }).collect();
+    let mut block = BlockEntropyCalculation::new(4);
+    block.update(&counter);
+    assert_eq!(block.block_entropy(1), dec!(8));
+    assert_eq!(block.rate(1), dec!(8));
+    assert!(block.per_sample(4).lt(&dec!(2.01)));
+    assert_eq!(block.finalize(), Dec::ZERO);
+
+    assert!(! BlockEntropyCalculation::is_valid(Mode::Bytes, 1, 9));
+    assert!(BlockEntropyCalculation::is_valid(Mode::Bits(BitOrder::LsbFirst), 1, 64));
+    assert!(! BlockEntropyCalculation::is_valid(Mode::Bytes, 0, 2));
+}
+
+#[test]
+fn min_entropy() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed by mpmath.
+    let ret = Entest::test(data);
+    assert!(close(ret.mcv_upper_bound(), dec!(0.007916032013410222495)));
+    assert!(close(ret.min_entropy(), dec!(6.981006837828104703)));
+    assert_eq!(ret.min_entropy(), MostCommonValueCalculation::test(data));
+    assert_eq!(mcv::mcv_min_entropy(61, LEN as u64), ret.min_entropy());
+
+    // the counts of chi-square calculation can be reused, and 8-bit symbols are bytes.
+    let mut chi = ChiSquareCalculation::new();
+    chi.update(data);
+    assert_eq!(MostCommonValueCalculation::from(chi).finalize(), ret.min_entropy());
+    let mut symbols = MostCommonValueCalculation::with_symbols(8, BitOrder::MsbFirst);
+    symbols.update(data);
+    assert_eq!(symbols.outcome(), MostCommonValueCalculation::from(chi).outcome());
+
+    let bits = Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), data);
+    assert!(close(bits.mcv_upper_bound(), dec!(0.5055254954332362678)));
+    assert!(close(bits.min_entropy(), dec!(0.9841442407263260034)));
+
+    // a constant has no min-entropy, and the bound never exceeds 1.
+    assert_eq!(Entest::test(&[0u8; LEN]).min_entropy(), Dec::ZERO);
+    assert_eq!(mcv::mcv_upper_bound(1, 1), Dec::ONE);
+    assert!(Entest::test(&[]).min_entropy().is_nan());
+}
+
+#[test]
+fn renyi_entropy() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed by mpmath.
+    let ret = Entest::test(data);
+    let histogram = ret.histogram();
+    let (hartley, collision, min) = histogram.renyi_orders();
+    assert_eq!(hartley, dec!(8));
+    assert!(close(collision, dec!(7.959299313285451154)), "{collision}");
+    assert!(close(min, dec!(7.391190757324476071)), "{min}");
+    assert!(close(histogram.renyi(dec!(0.5)), dec!(7.989421863146626777)));
+    assert!(close(histogram.renyi(dec!(3)), dec!(7.940275949997163889)));
+    assert!(close(histogram.renyi(dec!(100)), dec!(7.465745967928640559)));
+    assert_eq!(histogram.renyi(Dec::ONE), *ret.shannon());
+
+    // every order is the same for a uniform distribution over observed values.
+    let flat: Vec<u8> = (0..LEN).map(|i| { (i % 16) as u8 }).collect();
+    let ret = Entest::test(&flat);
+    let histogram = ret.histogram();
+    for alpha in [dec!(0), dec!(0.5), dec!(1), dec!(2), dec!(7), renyi::MIN] {
+        assert!(close(histogram.renyi(alpha), dec!(4)), "alpha={alpha}");
+    }
+
+    assert!(histogram.renyi(dec!(-1)).is_nan());
+    assert!(Entest::test(&[]).histogram().renyi(renyi::COLLISION).is_nan());
+}
+
+#[test]
+fn collision_estimate() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-12)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed by mpmath, using the incomplete gamma function.
+    assert!(close(collision::collision_expected_time(dec!(0.6), 2), dec!(2.48)));
+    assert!(close(collision::collision_expected_time(dec!(0.01), 256), dec!(20.64440145673120927)));
+
+    let mut coll = CollisionCalculation::new();
+    for part in data.chunks(13) {
+        coll.update(part);
+    }
+    assert_eq!(coll.collisions(), 496);
+    assert!(close(coll.mean(), dec!(20.64314516129032258)));
+    assert!(close(coll.probability(), dec!(0.03111631561609264751)), "{}", coll.probability());
+    assert!(close(coll.finalize(), dec!(5.006184944266550320)), "{}", coll.finalize());
+    assert_eq!(coll.finalize(), CollisionCalculation::test(data));
+
+    let mut bits = CollisionCalculation::with_mode(Mode::Bits(BitOrder::MsbFirst));
+    bits.update(data);
+    assert_eq!(bits.collisions(), 32831);
+    assert!(close(bits.finalize(), dec!(0.7927250486719268197)), "{}", bits.finalize());
+
+    // a constant collides at every second sample, that is no entropy.
+    assert_eq!(CollisionCalculation::test(&[0u8; LEN]), Dec::ZERO);
+
+    // alternating bits have the uniform mean, so the estimate is not lower than 1 bit.
+    let mut alternating = CollisionCalculation::with_mode(Mode::Bits(BitOrder::MsbFirst));
+    alternating.update(&[0x55, 0x55, 0x55, 0x54]);
+    assert_eq!(alternating.finalize(), dec!(1));
+
+    assert!(CollisionCalculation::test(&[1, 2, 3]).is_nan());
+}
+
+#[test]
+fn markov_estimate() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed by mpmath.
+    let mut msb = MarkovCalculation::new();
+    for part in data.chunks(13) {
+        msb.update(part);
+    }
+    assert_eq!(msb.samples(), (LEN * 8) as u64);
+    assert!(close(msb.finalize(), dec!(0.9870617179531662854)), "{}", msb.finalize());
+    assert_eq!(msb.finalize(), MarkovCalculation::test(data));
+
+    let mut lsb = MarkovCalculation::with_order(BitOrder::LsbFirst);
+    lsb.update(data);
+    assert!(close(lsb.finalize(), dec!(0.9910765467944021147)), "{}", lsb.finalize());
+
+    // balanced bits with sticky transitions: shannon entropy is 1, but the most likely path is all zeros.
+    let sticky: Vec<u8> = [0x00, 0xff].repeat(512);
+    let mut markov = MarkovCalculation::new();
+    markov.update(&sticky);
+    assert_eq!(*Entest::test_mode(Mode::Bits(BitOrder::MsbFirst), &sticky).shannon(), dec!(1));
+    assert_eq!(markov.initial(0), dec!(0.5));
+    assert!(close(markov.transition(0, 0), dec!(0.875)));
+    assert!(close(markov.finalize(), dec!(0.1986030268577140050)), "{}", markov.finalize());
+
+    assert_eq!(MarkovCalculation::test(&[0u8; LEN]), Dec::ZERO);
+    // alternating bits are fully determined by the first bit.
+    assert!(close(MarkovCalculation::test(&[0x55u8; LEN]), dec!(1) / dec!(128)));
+    assert!(MarkovCalculation::new().finalize().is_nan());
+}
+
+#[test]
+fn compression_estimate() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-9)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed in double precision, the sum of G(z) is checked against the double sum of SP 800-90B.
+    assert!(close(compression::compression_expected_mean(dec!(0.02), 13653), dec!(5.2169219423727125)));
+
+    // long distances are summed in chunks, with a small relative error.
+    let near = |a: Dec, b: Dec| { a.sub(b).abs().div(b).lt(&dec!(1e-6)) };
+    assert!(near(compression::compression_expected_mean(dec!(0.9), 100000), dec!(0.9430253896542171)));
+    assert!(near(compression::compression_expected_mean(dec!(0.999), 100000), dec!(0.015318223489912443)));
+
+    // no test block after the dictionary.
+    for blocks in [0, 500, compression::COMPRESSION_DICTIONARY_BLOCKS] {
+        assert!(compression::compression_expected_mean(dec!(0.5), blocks).is_nan());
+        assert!(compression::compression_probability(dec!(3), blocks).is_nan());
+    }
+
+    let mut msb = CompressionCalculation::new();
+    for part in data.chunks(13) {
+        msb.update(part);
+    }
+    assert_eq!(msb.blocks(), (LEN * 8 / 6) as u64);
+    assert_eq!(msb.test_blocks(), msb.blocks() - 1000);
+    assert!(close(msb.mean(), dec!(5.223848569294756)), "{}", msb.mean());
+    assert!(close(msb.std_dev(), dec!(1.0129265941733334)), "{}", msb.std_dev());
+    assert!(close(msb.probability(), dec!(0.03900150573111488)), "{}", msb.probability());
+    assert!(close(msb.finalize(), dec!(0.7800543944329705)), "{}", msb.finalize());
+
+    let mut lsb = CompressionCalculation::with_order(BitOrder::LsbFirst);
+    lsb.update(data);
+    assert!(close(lsb.finalize(), dec!(0.7851342856390411)), "{}", lsb.finalize());
+
+    // a counter has max order-0 entropy, but it is found by the distances of blocks.
+    let counter: Vec<u8> = (0..LEN).map(|i| { i as u8 }).collect();
+    let mut comp = CompressionCalculation::new();
+    comp.update(&counter);
+    assert!(close(comp.finalize(), dec!(0.6414080842944089)), "{}", comp.finalize());
+
+    let mut zero = CompressionCalculation::new();
+    zero.update(&[0u8; LEN]);
+    assert!(zero.finalize().lt(&dec!(0.01)), "{}", zero.finalize());
+
+    let mut short = CompressionCalculation::new();
+    short.update(&[0u8; 750]);
+    assert!(short.finalize().is_nan());
+}
+
+#[test]
+fn tuple_estimates() {
+    let close = |a: Dec, b: Dec| { a.sub(b).abs().lt(&dec!(1e-15)) };
+    let data = include_bytes!("tests.rand");
+
+    // references are computed by mpmath, counting all tuples of every length.
+    let mut bytes = TupleCalculation::new();
+    for part in data.chunks(13) {
+        bytes.update(part);
+    }
+    let profile = bytes.profile();
+    assert_eq!((profile.t(), profile.longest_repeat()), (1, 3));
+    assert!(close(profile.t_tuple(), dec!(6.981006837828104704)), "{}", profile.t_tuple());
+    assert!(close(profile.lrs(), dec!(7.405923077355774589)), "{}", profile.lrs());
+    assert_eq!(bytes.finalize(), profile.t_tuple());
+    // for 1-tuples, it is the most common value estimate.
+    let ret = Entest::test(data);
+    assert_eq!(profile.t_tuple(), ret.min_entropy());
+
+    let mut bits = TupleCalculation::with_mode(Mode::Bits(BitOrder::MsbFirst));
+    bits.update(data);
+    let profile = bits.profile();
+    assert_eq!((profile.t(), profile.longest_repeat()), (12, 29));
+    assert!(close(profile.t_tuple(), dec!(0.9074847305267095736)), "{}", profile.t_tuple());
+    assert!(close(profile.lrs(), dec!(0.9831696798816987061)), "{}", profile.lrs());
+
+    let mut text = TupleCalculation::new();
+    text.update(include_bytes!("../tests/corpus/text.txt"));
+    let profile = text.profile();
+    assert_eq!((profile.t(), profile.longest_repeat()), (2, 26));
+    assert!(close(profile.t_tuple(), dec!(2.291615793779775632)), "{}", profile.t_tuple());
+    assert!(close(profile.lrs(), dec!(0.6505478741672854248)), "{}", profile.lrs());
+
+    // a repeated block is not found by the other estimates, but LRS is very low.
+    let repeated = [&data[..], &data[..256]].concat();
+    let mut tuple = TupleCalculation::new();
+    tuple.update(&repeated);
+    let profile = tuple.profile();
+    assert_eq!(profile.longest_repeat(), 256);
+    assert_eq!(profile.collision_pairs(256), 1);
+    assert!(close(profile.lrs(), dec!(0.08066107233696511031)), "{}", profile.lrs());
+    assert_eq!(tuple.finalize(), profile.lrs());
+
+    // a constant repeats at every length, so there is no entropy.
+    let mut zero = TupleCalculation::new();
+    zero.update(&[0u8; LEN]);
+    let profile = zero.profile();
+    assert_eq!(profile.longest_repeat(), (LEN - 1) as u64);
+    assert_eq!((profile.max_count(1), profile.max_count((LEN - 1) as u64)), (LEN as u64, 2));
+    assert_eq!((profile.collision_pairs(1), profile.collision_pairs((LEN - 1) as u64)), ((LEN * (LEN - 1) / 2) as u64, 1));
+    assert_eq!(zero.finalize(), Dec::ZERO);
+
+    assert!(TupleCalculation::new().finalize().is_nan());
+    let mut short = TupleCalculation::new();
+    short.update(&[1, 2, 3]);
+    assert!(short.profile().t_tuple().is_nan());
+    assert!(short.profile().lrs().is_nan());
+
+    // the update that exceeds max samples is dropped, and later updates are ignored.
+    let mut limited = TupleCalculation::with_mode(Mode::Bits(BitOrder::MsbFirst)).with_max_samples(LEN as u64);
+    limited.update(&data[..LEN / 8]);
+    assert!(! limited.is_overflow());
+    limited.update(&data[..1]);
+    limited.update(&[]);
+    assert!(limited.is_overflow());
+    assert_eq!(limited.samples(), (LEN / 8 * 8) as u64);
+    assert!(limited.finalize().is_nan());
+    assert_eq!(TupleCalculation::new().with_max_samples(u64::MAX).max_samples(), tuple::MAX_TUPLE_SAMPLES);
+}
+
+#[test]
+fn build_info() {
+    let info = BuildInfo::CURRENT;
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.enabled_features().any(|name| { name == "lite" }), cfg!(feature="lite"));
+    assert_eq!(info.dec_digits, if cfg!(feature="lite") { 19 } else { 77 });
+
+    let report = info.to_string();
+    assert!(report.contains("LOG_SQRT_PI = "));
+    assert!(report.contains(info.dec_type));
+}
+
+#[test]
+fn terse_many() {
+    let ret = Entest::test(&[0u8; LEN]);
+    assert_eq!(
+        ret.terse(false).with_name("a,\"b\".bin").to_string(),
+        "0,File-bytes,Entropy,Chi-square,Mean,Monte-Carlo-Pi,Serial-Correlation,File-name\n\
+         1,10240,0.000000,2611200.000000,0.000000,4.000000,nan,\"a,\"\"b\"\".bin\"\n"
+    );
+    assert_eq!(
+        ret.terse(true).with_header(false).with_name("zero.bin").to_string().lines().nth(1),
+        Some("3,0,10240,1.000000,zero.bin")
+    );
+}
+
+#[cfg(feature="serde")]
+#[test]
+fn serde_roundtrip() {
+    let predefined_data_buf = include_bytes!("tests.rand");
+
+    let ret = Entest::test_mode(Mode::Bits(BitOrder::LsbFirst), predefined_data_buf);
+    let json = serde_json::to_value(ret).unwrap();
+    assert_eq!(json["mode"], serde_json::json!({ "bits": "lsb_first" }));
+    assert_eq!(json["samples"], (LEN * 8) as u64);
+    assert_eq!(json["counts"].as_array().unwrap().len(), 2);
+    assert_eq!(json["mc_error"], ret.mc_error().to_string());
+
+    let de: EntestResult = serde_json::from_value(json).unwrap();
+    assert_eq!(de.mode(), ret.mode());
+    assert_eq!(de.chi(), ret.chi());
+    assert_eq!(de.sc(), ret.sc());
+    assert_eq!(de.compression(), ret.compression());
+    assert_eq!(de.histogram().counts(), ret.histogram().counts());
+}
+
+#[test]
+fn thresholds() {
+    let predefined_data_buf = include_bytes!("tests.rand");
+    let ret = Entest::test(predefined_data_buf);
+
+    assert!(!Thresholds::NONE.is_enabled());
+    assert!(Thresholds::NONE.check(&ret).is_pass());
+
+    let thresholds =
+        Thresholds::new()
+        .with_min_shannon_entropy(dec!(7.9))
+        .with_chi_prob_band(dec!(1), dec!(99))
+        .with_max_mc_error(dec!(1))
+        .with_max_abs_sc(dec!(0.05));
+    assert!(thresholds.check(&ret).is_pass());
+
+    let strict = thresholds.with_min_shannon_entropy(dec!(7.99)).with_max_mc_error(dec!(0.1));
+    let verdict = strict.check(&ret);
+    assert!(!verdict.is_pass());
+    let failed: Vec<threshold::Check> = verdict.failures().map(|failure| { failure.check }).collect();
+    assert_eq!(failed, [threshold::Check::ShannonEntropy, threshold::Check::MonteCarloError]);
+
+    // min-entropy is the most common value estimate, that is less than shannon entropy.
+    let min_entropy = ret.min_entropy();
+    assert!(min_entropy.lt(ret.shannon()));
+    assert!(thresholds.with_min_entropy(min_entropy).check(&ret).is_pass());
+    let verdict = thresholds.with_min_entropy(ret.shannon().sub(dec!(0.01))).check(&ret);
+    let failed: Vec<threshold::Check> = verdict.failures().map(|failure| { failure.check }).collect();
+    assert_eq!(failed, [threshold::Check::MinEntropy]);
+
+    // NaN always fails an enabled check.
+    let zero = Entest::test(&[0u8; LEN]);
+    let verdict = Thresholds::new().with_max_abs_sc(dec!(1)).check(&zero);
+    assert_eq!(verdict.failures().count(), 1);
+}
@@ -0,0 +1,224 @@
+//! pass/fail checks of [EntestResult] against configurable thresholds.
+
+use super::*;
+
+/// the kind of a threshold check.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// shannon entropy must not be less than [Thresholds::min_shannon_entropy].
+    ShannonEntropy,
+
+    /// most common value estimate of min-entropy must not be less than [Thresholds::min_entropy].
+    MinEntropy,
+
+    /// chi-square exceedance percent must be inside of [Thresholds::chi_prob_band].
+    ChiProb,
+
+    /// monte-carlo error percent must not be greater than [Thresholds::max_mc_error].
+    MonteCarloError,
+
+    /// absolute serial correlation coefficient must not be greater than [Thresholds::max_abs_sc].
+    SerialCorrelation,
+}
+
+impl Check {
+    /// all kinds of check, in the order of evaluation.
+    pub const ALL: [Self; 5] = [Self::ShannonEntropy, Self::MinEntropy, Self::ChiProb, Self::MonteCarloError, Self::SerialCorrelation];
+
+    /// short name of this check.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ShannonEntropy => "shannon entropy",
+            Self::MinEntropy => "min-entropy",
+            Self::ChiProb => "chi-square probability",
+            Self::MonteCarloError => "monte-carlo error",
+            Self::SerialCorrelation => "serial correlation",
+        }
+    }
+}
+
+/// Failure describes a failed check.
+#[derive(Debug, Copy, Clone)]
+pub struct Failure {
+    /// which check is failed.
+    pub check: Check,
+
+    /// the value that was checked (NaN if it is not available, such as for empty input).
+    pub value: Dec,
+
+    /// the allowed lower bound, if any.
+    pub min: Option<Dec>,
+
+    /// the allowed upper bound, if any.
+    pub max: Option<Dec>,
+}
+
+impl core::fmt::Display for Failure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} is {}", self.check.name(), self.value)?;
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, ", expected {min} ..= {max}"),
+            (Some(min), None) => write!(f, ", expected at least {min}"),
+            (None, Some(max)) => write!(f, ", expected at most {max}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Thresholds for deciding whether an [EntestResult] passes.
+///
+/// every threshold is optional, and `None` means the check is disabled.
+/// a value that is NaN (such as results of empty input) always fails an enabled check.
+#[derive(Debug, Copy, Clone, Default)]
+#[non_exhaustive]
+pub struct Thresholds {
+    /// minimum shannon entropy, in bits per sample.
+    pub min_shannon_entropy: Option<Dec>,
+
+    /// minimum most common value estimate of min-entropy, in bits per sample (compared with [EntestResult::min_entropy]).
+    pub min_entropy: Option<Dec>,
+
+    /// allowed range of chi-square exceedance, in percent (compared with [EntestResult::chi_prob_percent]).
+    pub chi_prob_band: Option<(Dec, Dec)>,
+
+    /// maximum error of monte-carlo pi, in percent (compared with [EntestResult::mc_error]).
+    pub max_mc_error: Option<Dec>,
+
+    /// maximum absolute value of serial correlation coefficient.
+    pub max_abs_sc: Option<Dec>,
+}
+
+impl Thresholds {
+    /// no thresholds, every check is disabled.
+    pub const NONE: Self =
+        Self {
+            min_shannon_entropy: None,
+            min_entropy: None,
+            chi_prob_band: None,
+            max_mc_error: None,
+            max_abs_sc: None,
+        };
+
+    /// create thresholds with every check disabled.
+    ///
+    /// this just copy from [Thresholds::NONE].
+    pub const fn new() -> Self {
+        Self::NONE
+    }
+
+    /// set [Thresholds::min_shannon_entropy].
+    pub const fn with_min_shannon_entropy(mut self, min: Dec) -> Self {
+        self.min_shannon_entropy = Some(min);
+        self
+    }
+
+    /// set [Thresholds::min_entropy].
+    pub const fn with_min_entropy(mut self, min: Dec) -> Self {
+        self.min_entropy = Some(min);
+        self
+    }
+
+    /// set [Thresholds::chi_prob_band].
+    pub const fn with_chi_prob_band(mut self, low: Dec, high: Dec) -> Self {
+        self.chi_prob_band = Some((low, high));
+        self
+    }
+
+    /// set [Thresholds::max_mc_error].
+    pub const fn with_max_mc_error(mut self, max: Dec) -> Self {
+        self.max_mc_error = Some(max);
+        self
+    }
+
+    /// set [Thresholds::max_abs_sc].
+    pub const fn with_max_abs_sc(mut self, max: Dec) -> Self {
+        self.max_abs_sc = Some(max);
+        self
+    }
+
+    /// checks whether any threshold is enabled.
+    pub const fn is_enabled(&self) -> bool {
+        self.min_shannon_entropy.is_some() || self.min_entropy.is_some() || self.chi_prob_band.is_some() || self.max_mc_error.is_some() || self.max_abs_sc.is_some()
+    }
+
+    /// evaluate one check. returns None if it is passed or disabled.
+    pub const fn check_one(&self, check: Check, er: &EntestResult) -> Option<Failure> {
+        let (value, min, max) =
+            match check {
+                Check::ShannonEntropy => (*er.shannon(), self.min_shannon_entropy, None),
+                Check::MinEntropy => (er.min_entropy(), self.min_entropy, None),
+                Check::ChiProb => {
+                    let (min, max) =
+                        match self.chi_prob_band {
+                            Some((min, max)) => (Some(min), Some(max)),
+                            None => (None, None),
+                        };
+                    (er.chi_prob_percent(), min, max)
+                },
+                Check::MonteCarloError => (er.mc_error(), None, self.max_mc_error),
+                Check::SerialCorrelation => {
+                    let sc = *er.sc();
+                    (if sc.is_nan() { sc } else { sc.abs() }, None, self.max_abs_sc)
+                },
+            };
+
+        if min.is_none() && max.is_none() {
+            return None;
+        }
+
+        let failed =
+            value.is_nan()
+            ||
+            match min {
+                Some(ref min) => value.lt(min),
+                None => false,
+            }
+            ||
+            match max {
+                Some(ref max) => value.gt(max),
+                None => false,
+            };
+
+        if failed {
+            Some(Failure { check, value, min, max })
+        } else {
+            None
+        }
+    }
+
+    /// evaluate all enabled checks.
+    pub const fn check(&self, er: &EntestResult) -> Verdict {
+        let mut failures = [None; Check::ALL.len()];
+        let mut i = 0;
+        while i < Check::ALL.len() {
+            failures[i] = self.check_one(Check::ALL[i], er);
+            i += 1;
+        }
+        Verdict { failures }
+    }
+}
+
+/// Verdict is the result of [Thresholds::check].
+#[derive(Debug, Copy, Clone)]
+pub struct Verdict {
+    failures: [Option<Failure>; Check::ALL.len()],
+}
+
+impl Verdict {
+    /// checks whether all enabled checks is passed.
+    pub const fn is_pass(&self) -> bool {
+        let mut i = 0;
+        while i < self.failures.len() {
+            if self.failures[i].is_some() {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// iterate over failed checks.
+    pub fn failures(&self) -> impl Iterator<Item = &Failure> {
+        self.failures.iter().flatten()
+    }
+}
@@ -0,0 +1,133 @@
+//! byte transforms applied to input before testing.
+
+/// Transform modifies bytes in place, before they are provided to [Entest::update](crate::Entest::update) or [EntropyTest::update](crate::EntropyTest::update).
+///
+/// transforms can be composed by [Transform::then].
+pub trait Transform {
+    /// transform `bytes` in place.
+    fn transform(&mut self, bytes: &mut [u8]);
+
+    /// chain `next` after this transform.
+    #[inline(always)]
+    fn then<T: Transform>(self, next: T) -> Chain<Self, T>
+    where
+        Self: Sized,
+    {
+        Chain { first: self, second: next }
+    }
+}
+
+impl<T: Transform + ?Sized> Transform for &mut T {
+    #[inline(always)]
+    fn transform(&mut self, bytes: &mut [u8]) {
+        (**self).transform(bytes)
+    }
+}
+
+/// `None` does not change anything.
+impl<T: Transform> Transform for Option<T> {
+    #[inline(always)]
+    fn transform(&mut self, bytes: &mut [u8]) {
+        if let Some(t) = self {
+            t.transform(bytes);
+        }
+    }
+}
+
+/// Identity does not change anything.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Identity;
+
+impl Transform for Identity {
+    #[inline(always)]
+    fn transform(&mut self, _bytes: &mut [u8]) {}
+}
+
+/// Chain applies two transforms in order. created by [Transform::then].
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Transform, B: Transform> Transform for Chain<A, B> {
+    #[inline(always)]
+    fn transform(&mut self, bytes: &mut [u8]) {
+        self.first.transform(bytes);
+        self.second.transform(bytes);
+    }
+}
+
+/// FoldCase folds upper case letters to lower case (this is `ent -f`).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum FoldCase {
+    /// fold letters of ISO 8859-1 (Latin-1), this is the behavior of `ent -f`.
+    ///
+    /// that is `A-Z` and `0xC0-0xDE` (except `0xD7`, the multiplication sign).
+    #[default]
+    Iso8859_1,
+
+    /// fold ASCII letters `A-Z` only.
+    Ascii,
+}
+
+impl FoldCase {
+    /// fold one byte.
+    #[inline(always)]
+    pub const fn fold(self, byte: u8) -> u8 {
+        match byte {
+            b'A'..=b'Z' => byte + 0x20,
+            0xC0..=0xDE if byte != 0xD7 && matches!(self, Self::Iso8859_1) => byte + 0x20,
+            _ => byte,
+        }
+    }
+
+    /// fold every byte in place.
+    #[inline(always)]
+    pub const fn apply(self, bytes: &mut [u8]) {
+        let mut i = 0;
+        while i < bytes.len() {
+            bytes[i] = self.fold(bytes[i]);
+            i += 1;
+        }
+    }
+}
+
+impl Transform for FoldCase {
+    #[inline(always)]
+    fn transform(&mut self, bytes: &mut [u8]) {
+        self.apply(bytes)
+    }
+}
+
+/// TransformReader applies a [Transform] to all bytes read from the inner reader.
+#[cfg(feature="std")]
+#[derive(Debug)]
+pub struct TransformReader<R, T> {
+    inner: R,
+    transform: T,
+}
+
+#[cfg(feature="std")]
+impl<R: std::io::Read, T: Transform> TransformReader<R, T> {
+    /// wraps `inner` reader with `transform`.
+    #[inline(always)]
+    pub const fn new(inner: R, transform: T) -> Self {
+        Self { inner, transform }
+    }
+
+    /// unwraps this `TransformReader`, returning the inner reader.
+    #[inline(always)]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature="std")]
+impl<R: std::io::Read, T: Transform> std::io::Read for TransformReader<R, T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.transform.transform(&mut buf[..len]);
+        Ok(len)
+    }
+}
@@ -0,0 +1,468 @@
+//! the t-tuple and longest repeated substring (LRS) estimates of min-entropy (NIST SP 800-90B sections 6.3.5 and 6.3.6).
+//!
+//! both estimates use the counts of repeated substrings of every length. these are computed from a suffix array
+//! and its LCP (longest common prefix) array, so the time is `O(N log N)` for `N` samples, even if the input has
+//! very long repeats.
+//!
+//! besides 1 byte of every kept sample, the memory is 16 bytes per sample for building the suffix array, and then
+//! 4 bytes per sample for the LCP array and up to 24 bytes per sample for the stack of lcp-intervals and the counts of
+//! every length up to the longest repeat. so it is at most about 29 bytes per sample, that is only for very long repeats (such as constant
+//! input); if the longest repeat is short (such as random input), it is about 17 bytes per sample.
+
+use super::*;
+use alloc::{vec, vec::Vec};
+
+/// the t-tuple estimate uses tuples whose most common one occurs at least this many times (`35`).
+pub const TUPLE_CUTOFF: u64 = 35;
+
+/// max number of samples (`2^32 - 1`), positions of the suffix array are u32.
+pub const MAX_TUPLE_SAMPLES: u64 = u32::MAX as u64;
+
+/// sort all suffixes of `samples`, where every sample is less than `alphabet`.
+///
+/// this is prefix doubling with counting sort: after the round of `k`, suffixes are ranked by their first `2k` samples.
+fn suffix_array(samples: &[u8], alphabet: usize) -> Vec<u32> {
+    let n = samples.len();
+    let mut sa: Vec<u32> = vec![0; n];
+    let mut rank: Vec<u32> = samples.iter().map(|&s| { s as u32 }).collect();
+    let mut tmp: Vec<u32> = vec![0; n];
+    let mut count: Vec<u32> = vec![0; alphabet.max(n) + 1];
+    if n == 0 {
+        return sa;
+    }
+
+    // sort by the first sample.
+    for &s in samples {
+        count[s as usize] += 1;
+    }
+    let mut sum = 0;
+    for c in count.iter_mut() {
+        let next = sum + *c;
+        *c = sum;
+        sum = next;
+    }
+    for (i, &s) in samples.iter().enumerate() {
+        sa[count[s as usize] as usize] = i as u32;
+        count[s as usize] += 1;
+    }
+    let mut classes = alphabet;
+
+    let mut k = 1;
+    while k < n {
+        // order by the second half: suffixes shorter than k first, then by the previous order.
+        let mut j = 0;
+        for i in (n - k)..n {
+            tmp[j] = i as u32;
+            j += 1;
+        }
+        for &p in sa.iter() {
+            if p as usize >= k {
+                tmp[j] = p - k as u32;
+                j += 1;
+            }
+        }
+
+        // stable counting sort by the first half.
+        count[..classes + 1].fill(0);
+        for &r in rank.iter() {
+            count[r as usize] += 1;
+        }
+        let mut sum = 0;
+        for c in count[..classes + 1].iter_mut() {
+            let next = sum + *c;
+            *c = sum;
+            sum = next;
+        }
+        for &p in tmp.iter() {
+            let r = rank[p as usize] as usize;
+            sa[count[r] as usize] = p;
+            count[r] += 1;
+        }
+
+        // new ranks of the first 2k samples.
+        let key = |rank: &[u32], p: usize| { (rank[p], rank.get(p + k).map(|&r| { r + 1 }).unwrap_or(0)) };
+        tmp[sa[0] as usize] = 0;
+        let mut r = 0;
+        for i in 1..n {
+            if key(&rank, sa[i] as usize) != key(&rank, sa[i - 1] as usize) {
+                r += 1;
+            }
+            tmp[sa[i] as usize] = r;
+        }
+        core::mem::swap(&mut rank, &mut tmp);
+        classes = r as usize + 1;
+        if classes == n {
+            break;
+        }
+        k *= 2;
+    }
+    sa
+}
+
+/// `lcp[i]` is the length of the longest common prefix of suffixes `sa[i - 1]` and `sa[i]` (Kasai's algorithm).
+///
+/// `lcp[0]` is zero.
+fn lcp_array(samples: &[u8], sa: &[u32]) -> Vec<u32> {
+    let n = samples.len();
+    let mut rank = vec![0u32; n];
+    for (i, &p) in sa.iter().enumerate() {
+        rank[p as usize] = i as u32;
+    }
+    let mut lcp = vec![0u32; n];
+    let mut h = 0;
+    for p in 0..n {
+        let r = rank[p] as usize;
+        if r == 0 {
+            h = 0;
+            continue;
+        }
+        let q = sa[r - 1] as usize;
+        while p + h < n && q + h < n && samples[p + h] == samples[q + h] {
+            h += 1;
+        }
+        lcp[r] = h as u32;
+        h = h.saturating_sub(1);
+    }
+    lcp
+}
+
+/// `n * (n - 1) / 2` without overflow.
+#[inline(always)]
+const fn pairs(n: u64) -> u64 {
+    if n < 2 {
+        0
+    } else if n.is_multiple_of(2) {
+        n / 2 * (n - 1)
+    } else {
+        n * ((n - 1) / 2)
+    }
+}
+
+/// `log2(x)` in double precision, for `x > 0`.
+///
+/// it is only used to compare roots quickly, so it is computed from the leading bit and the series of `atanh`
+/// (`ln(m) = 2 * atanh((m - 1) / (m + 1))` for the mantissa `m` in `[1, 2)`).
+fn log2_approx(x: u64) -> f64 {
+    let e = 63 - x.leading_zeros();
+    let m = x as f64 / (1u64 << e) as f64;
+    let z = (m - 1.0) / (m + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    let mut k = 1.0;
+    // `z <= 1/3`, so 20 terms are enough for double precision.
+    for _ in 0..20 {
+        sum += term / k;
+        term *= z2;
+        k += 2.0;
+    }
+    e as f64 + 2.0 * sum / core::f64::consts::LN_2
+}
+
+/// `log2` of `(count / total)^(1/w)` in double precision.
+fn log2_root(count: u64, total: u64, w: u64) -> f64 {
+    if count == 0 {
+        return f64::NEG_INFINITY;
+    }
+    (log2_approx(count) - log2_approx(total)) / w as f64
+}
+
+/// roots whose [log2_root] is less than the max by this are not computed exactly.
+const ROOT_TOLERANCE: f64 = 1e-12;
+
+/// the max of `(count(w) / total(w))^(1/w)` for `w` in `range`, where the fraction is at most 1.
+///
+/// the roots of all lengths are compared in double precision first, then only the lengths
+/// close to the max are computed with `ln` and `exp`. so it is fast for long ranges.
+/// returns NaN if `range` is empty.
+fn max_root(range: core::ops::RangeInclusive<u64>, count: impl Fn(u64) -> u64, total: impl Fn(u64) -> u64) -> Dec {
+    let mut max = f64::NEG_INFINITY;
+    for w in range.clone() {
+        let (c, t) = (count(w), total(w));
+        if c == t {
+            return Dec::ONE;
+        }
+        max = max.max(log2_root(c, t, w));
+    }
+
+    let mut best = Dec::NAN;
+    for w in range {
+        let (c, t) = (count(w), total(w));
+        if log2_root(c, t, w) < max - ROOT_TOLERANCE {
+            continue;
+        }
+        let pw = Dec::from_u64(c).div(Dec::from_u64(t));
+        let root = if w == 1 { pw } else { ln(pw).div(Dec::from_u64(w)).exp() };
+        if best.is_nan() || root.gt(&best) {
+            best = root;
+        }
+    }
+    best
+}
+
+/// RepetitionProfile is the counts of repeated substrings of every length, from [TupleCalculation::profile].
+#[derive(Debug, Clone)]
+pub struct RepetitionProfile {
+    /// total samples
+    samples: u64,
+    /// `max_counts[w - 1]` is the count of the most common `w`-tuple, for `w` up to the longest repeat
+    max_counts: Vec<u64>,
+    /// `pairs[w - 1]` is the number of pairs of equal `w`-tuples, for `w` up to the longest repeat
+    pairs: Vec<u64>,
+}
+
+impl RepetitionProfile {
+    /// compute the profile of `samples`, where every sample is less than `alphabet`.
+    fn new(samples: &[u8], alphabet: usize) -> Self {
+        let n = samples.len();
+        // the suffix array is not needed after the LCP array.
+        let lcp = lcp_array(samples, &suffix_array(samples, alphabet));
+        let longest = lcp.iter().copied().max().unwrap_or(0) as usize;
+
+        // every lcp-interval of `size` suffixes with lcp `l` and parent lcp `parent` is a substring
+        // that occurs `size` times, for every length in `parent + 1 ..= l`.
+        // the pairs are added to `parent + 1` and subtracted from `l + 1`, and the prefix sums of them are the pairs
+        // of every length. every prefix sum fits in u64, so the wrapping add and sub give the exact sums.
+        let mut max_counts = vec![0u64; longest];
+        let mut diff = vec![0u64; longest + 2];
+        let mut stack: Vec<(u32, u32)> = vec![(0, 0)];
+        for i in 1..=n {
+            let h = lcp.get(i).copied().unwrap_or(0);
+            let mut lb = (i - 1) as u32;
+            while h < stack[stack.len() - 1].0 {
+                let (l, left) = stack.pop().unwrap();
+                lb = left;
+                let size = (i as u64) - (lb as u64);
+                let parent = h.max(stack[stack.len() - 1].0);
+                max_counts[l as usize - 1] = max_counts[l as usize - 1].max(size);
+                diff[parent as usize + 1] = diff[parent as usize + 1].wrapping_add(pairs(size));
+                diff[l as usize + 1] = diff[l as usize + 1].wrapping_sub(pairs(size));
+            }
+            if h > stack[stack.len() - 1].0 {
+                stack.push((h, lb));
+            }
+        }
+
+        // the prefix of a tuple occurs at least as many times as the tuple, so shorter ones are not less common.
+        let mut max = 1;
+        for count in max_counts.iter_mut().rev() {
+            max = max.max(*count);
+            *count = max;
+        }
+        let mut sum = 0u64;
+        for d in diff.iter_mut() {
+            sum = sum.wrapping_add(*d);
+            *d = sum;
+        }
+        diff.remove(0);
+        diff.truncate(longest);
+
+        Self { samples: n as u64, max_counts, pairs: diff }
+    }
+
+    /// get the number of samples.
+    #[inline(always)]
+    pub const fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// the length of the longest repeated substring (`v` of SP 800-90B).
+    #[inline(always)]
+    pub fn longest_repeat(&self) -> u64 {
+        self.max_counts.len() as u64
+    }
+
+    /// the count of the most common `w`-tuple.
+    ///
+    /// it is 1 for lengths longer than [RepetitionProfile::longest_repeat] (if there is such a tuple), and 0 for `w = 0`.
+    pub fn max_count(&self, w: u64) -> u64 {
+        if w == 0 || w > self.samples {
+            return 0;
+        }
+        self.max_counts.get((w - 1) as usize).copied().unwrap_or(1)
+    }
+
+    /// the number of pairs of equal `w`-tuples (at different positions), that is `sum(C(count, 2))` over all `w`-tuples.
+    pub fn collision_pairs(&self, w: u64) -> u64 {
+        if w == 0 {
+            return 0;
+        }
+        self.pairs.get((w - 1) as usize).copied().unwrap_or(0)
+    }
+
+    /// the largest length `t` that the most common `t`-tuple occurs at least [TUPLE_CUTOFF] times.
+    pub fn t(&self) -> u64 {
+        self.max_counts.iter().take_while(|&&count| { count >= TUPLE_CUTOFF }).count() as u64
+    }
+
+    /// the t-tuple estimate of min-entropy in bits per sample (SP 800-90B section 6.3.5).
+    ///
+    /// the probability of the most likely sample is the max of `P[i]^(1/i)` for `i` in `1..=t`,
+    /// where `P[i]` is the fraction of the most common `i`-tuple.
+    /// returns NaN if there is no such `t` (every sample occurs less than [TUPLE_CUTOFF] times).
+    pub fn t_tuple(&self) -> Dec {
+        let t = self.t();
+        if t == 0 {
+            return Dec::NAN;
+        }
+        let p = max_root(1..=t, |i| { self.max_count(i) }, |i| { self.samples - i + 1 });
+        mcv::min_entropy_of(mcv::upper_bound(p, self.samples))
+    }
+
+    /// the LRS estimate of min-entropy in bits per sample (SP 800-90B section 6.3.6).
+    ///
+    /// the probability of the most likely sample is the max of `P[W]^(1/W)` for `W` in `t + 1 ..= longest_repeat`,
+    /// where `P[W]` is the collision probability of `W`-tuples, that is [RepetitionProfile::collision_pairs]
+    /// divided by the number of pairs of `W`-tuples.
+    /// returns NaN if that range is empty.
+    pub fn lrs(&self) -> Dec {
+        let (u, v) = (self.t() + 1, self.longest_repeat());
+        if u > v {
+            return Dec::NAN;
+        }
+        let p = max_root(u..=v, |w| { self.collision_pairs(w) }, |w| { pairs(self.samples - w + 1) });
+        mcv::min_entropy_of(mcv::upper_bound(p, self.samples))
+    }
+}
+
+/// Computes the t-tuple and LRS estimates of min-entropy.
+///
+/// all samples are kept (one byte per sample, also in bit mode), then [TupleCalculation::profile] sorts them.
+/// at most [TupleCalculation::max_samples] samples are kept: the update that exceeds it is dropped and the state is
+/// overflowed, then later updates are ignored and [TupleCalculation::finalize] returns NaN.
+/// for random input, the t-tuple estimate is a bit less than [Mode::sample_bits], and the LRS estimate is close to it.
+/// repetitive structure (such as a repeated block) makes them much lower.
+#[derive(Debug, Clone)]
+pub struct TupleCalculation {
+    /// all samples
+    samples: Vec<u8>,
+    /// sampling mode
+    mode: Mode,
+    /// max number of samples
+    max_samples: u64,
+    /// whether an update is dropped by `max_samples` (or allocation failure)
+    overflow: bool,
+}
+
+impl Default for TupleCalculation {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TupleCalculation {
+    /// create new blanket state for t-tuple and LRS estimates.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::with_mode(Mode::Bytes)
+    }
+
+    /// creates new blanket state for t-tuple and LRS estimates with the provided [Mode].
+    #[inline(always)]
+    pub const fn with_mode(mode: Mode) -> Self {
+        Self { samples: Vec::new(), mode, max_samples: MAX_TUPLE_SAMPLES, overflow: false }
+    }
+
+    /// set the max number of samples, that should be used on blanket state.
+    ///
+    /// it is at most [MAX_TUPLE_SAMPLES] (the default).
+    #[inline(always)]
+    pub const fn with_max_samples(mut self, max: u64) -> Self {
+        self.max_samples = if max < MAX_TUPLE_SAMPLES { max } else { MAX_TUPLE_SAMPLES };
+        self
+    }
+
+    /// get the max number of samples of current state.
+    #[inline(always)]
+    pub const fn max_samples(&self) -> u64 {
+        self.max_samples
+    }
+
+    /// checks whether any update is dropped, because it exceeds [TupleCalculation::max_samples]
+    /// (or the memory of samples can not be allocated).
+    #[inline(always)]
+    pub const fn is_overflow(&self) -> bool {
+        self.overflow
+    }
+
+    /// get the sampling mode of current state.
+    #[inline(always)]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// get the samples of current state.
+    #[inline(always)]
+    pub fn samples(&self) -> u64 {
+        self.samples.len() as u64
+    }
+
+    /// apply byte stream to tuple state.
+    ///
+    /// if the samples of `bytes` exceeds [TupleCalculation::max_samples], they are dropped and the state is overflowed.
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        if self.overflow {
+            return self;
+        }
+        let additional = (bytes.len() as u64).saturating_mul(self.mode.sample_bits() as u64);
+        if additional > self.max_samples - self.samples() || self.samples.try_reserve(additional as usize).is_err() {
+            self.overflow = true;
+            return self;
+        }
+        match self.mode {
+            Mode::Bytes => {
+                self.samples.extend_from_slice(bytes);
+            },
+            Mode::Bits(order) => {
+                for &byte in bytes {
+                    self.samples.extend_from_slice(&order.expand(byte));
+                }
+            },
+        }
+        self
+    }
+
+    /// counts of repeated substrings of current samples, for [RepetitionProfile::t_tuple] and [RepetitionProfile::lrs].
+    ///
+    /// this sorts all suffixes, so it should be called once for both estimates.
+    /// if the state is overflowed, it is the profile of samples before the dropped update.
+    pub fn profile(&self) -> RepetitionProfile {
+        RepetitionProfile::new(&self.samples, self.mode.bins())
+    }
+
+    /// get finalize min-entropy of current byte stream, that is the lower one of the t-tuple and LRS estimates.
+    ///
+    /// returns NaN if both are NaN, or the state is overflowed.
+    pub fn finalize(&self) -> Dec {
+        if self.overflow {
+            return Dec::NAN;
+        }
+        let profile = self.profile();
+        let (t_tuple, lrs) = (profile.t_tuple(), profile.lrs());
+        if t_tuple.is_nan() || lrs.lt(&t_tuple) { lrs } else { t_tuple }
+    }
+
+    /// get the outcome of current state, that is expected to be (less than) [Mode::sample_bits] for random input.
+    ///
+    /// p-value is not defined for min-entropy.
+    pub fn outcome(&self) -> TestOutcome {
+        TestOutcome::new(self.finalize(), Dec::from_u32(self.mode.sample_bits()), self.samples())
+    }
+}
+
+impl EntropyTest for TupleCalculation {
+    #[inline(always)]
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes);
+    }
+
+    #[inline(always)]
+    fn finalize(&self) -> Dec {
+        Self::finalize(self)
+    }
+    #[inline(always)]
+    fn outcome(&self) -> TestOutcome {
+        Self::outcome(self)
+    }
+}
@@ -0,0 +1,29 @@
+#!/bin/sh
+# regenerate the expected outputs of this corpus from the original `ent`.
+#
+# usage: ./regen.sh [ENT]
+#
+# ENT defaults to `ent` in PATH. record the version of it in README.md
+# (see "oracle") when the outputs are changed.
+set -e
+cd "$(dirname "$0")"
+ENT="${1:-ent}"
+RAND=../../src/tests.rand
+
+if ! command -v "$ENT" > /dev/null; then
+    echo "regen.sh: $ENT not found, install ent from https://www.fourmilab.ch/random/" >&2
+    exit 1
+fi
+
+"$ENT" $RAND > rand.ent
+"$ENT" -b $RAND > rand.b.ent
+"$ENT" -c $RAND > rand.c.ent
+"$ENT" zeros.bin > zeros.ent
+"$ENT" -b zeros.bin > zeros.b.ent
+"$ENT" counter.bin > counter.ent
+"$ENT" -c counter.bin > counter.c.ent
+"$ENT" -b -c counter.bin > counter.bc.ent
+"$ENT" text.txt > text.ent
+"$ENT" -f text.txt > text.f.ent
+"$ENT" -c -f text.txt > text.cf.ent
+"$ENT" short.bin > short.ent